@@ -0,0 +1,57 @@
+//! Criterion benchmarks for the KEM operations and key serialization.
+//!
+//! `make bench-save` records a baseline (default name `main`) and
+//! `make bench-check` compares against it, failing if Criterion reports a
+//! statistically significant regression.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use mce::{ParameterSet, PublicKey};
+
+fn kem(c: &mut Criterion) {
+    let params = ParameterSet::compiled();
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = mce::generate(&mut rng).unwrap();
+    let (ciphertext, _) = mce::encapsulate(&public_key, &mut rng).unwrap();
+
+    let mut group = c.benchmark_group(params.name());
+    // Key generation takes hundreds of milliseconds and restarts a random
+    // number of times, so keep the run short and tolerate more variation.
+    group.sample_size(10);
+    group.noise_threshold(0.25);
+    group.bench_function("keygen", |b| b.iter(|| mce::generate(&mut rng).unwrap()));
+    group.finish();
+
+    let mut group = c.benchmark_group(params.name());
+    group.noise_threshold(0.03);
+    group.bench_function("encapsulate", |b| {
+        b.iter(|| mce::encapsulate(black_box(&public_key), &mut rng).unwrap())
+    });
+    group.bench_function("decapsulate", |b| {
+        b.iter(|| mce::decapsulate(black_box(&ciphertext), black_box(&secret_key)).unwrap())
+    });
+    group.finish();
+}
+
+fn serialization(c: &mut Criterion) {
+    let params = ParameterSet::compiled();
+    let (public_key, _) = mce::generate(&mut rand::thread_rng()).unwrap();
+    let raw = public_key.as_bytes().to_vec();
+    let encoded = public_key.to_encoded();
+
+    let mut group = c.benchmark_group(format!("{}/public-key", params.name()));
+    group.throughput(Throughput::Bytes(raw.len() as u64));
+    group.bench_function("from_bytes", |b| {
+        b.iter(|| PublicKey::from_bytes(black_box(&raw)).unwrap())
+    });
+    group.bench_function("to_encoded", |b| {
+        b.iter(|| black_box(&public_key).to_encoded())
+    });
+    group.bench_function("from_encoded", |b| {
+        b.iter(|| PublicKey::from_encoded(black_box(&encoded)).unwrap())
+    });
+    group.bench_function("clone", |b| b.iter(|| black_box(&public_key).clone()));
+    group.finish();
+}
+
+criterion_group!(benches, kem, serialization);
+criterion_main!(benches);
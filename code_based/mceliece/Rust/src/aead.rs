@@ -0,0 +1,143 @@
+//! AEAD algorithms used to protect data under a KEM-derived key.
+
+use std::fmt;
+use std::str::FromStr;
+
+use aes_gcm::aead::{AeadInPlace, KeyInit};
+use aes_gcm::{Aes128Gcm, Aes256Gcm};
+use chacha20poly1305::ChaCha20Poly1305;
+
+use crate::error::{Error, Result};
+
+/// Length of the longest key, and of the key buffer [`Cipher::new`] takes.
+pub(crate) const KEY_LEN: usize = 32;
+/// Nonce length shared by all algorithms.
+pub(crate) const NONCE_LEN: usize = 12;
+/// Authentication tag length shared by all algorithms.
+pub(crate) const TAG_LEN: usize = 16;
+
+/// An authenticated encryption algorithm.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Aead {
+    /// AES-256 in Galois/Counter Mode.
+    #[default]
+    Aes256Gcm,
+    /// ChaCha20-Poly1305 (RFC 8439).
+    ChaCha20Poly1305,
+    /// AES-128 in Galois/Counter Mode.
+    Aes128Gcm,
+}
+
+impl Aead {
+    /// Every algorithm, in identifier order.
+    pub const ALL: [Aead; 3] = [Aead::Aes256Gcm, Aead::ChaCha20Poly1305, Aead::Aes128Gcm];
+
+    /// The algorithm's name as accepted by [`FromStr`].
+    pub const fn name(self) -> &'static str {
+        match self {
+            Aead::Aes256Gcm => "aes-256-gcm",
+            Aead::ChaCha20Poly1305 => "chacha20-poly1305",
+            Aead::Aes128Gcm => "aes-128-gcm",
+        }
+    }
+
+    /// Key length in bytes.
+    pub const fn key_len(self) -> usize {
+        match self {
+            Aead::Aes256Gcm | Aead::ChaCha20Poly1305 => 32,
+            Aead::Aes128Gcm => 16,
+        }
+    }
+
+    /// The algorithm's identifier in the HPKE AEAD registry (RFC 9180).
+    pub const fn hpke_id(self) -> u16 {
+        match self {
+            Aead::Aes128Gcm => 0x0001,
+            Aead::Aes256Gcm => 0x0002,
+            Aead::ChaCha20Poly1305 => 0x0003,
+        }
+    }
+
+    /// The one-byte identifier stored in encrypted headers.
+    pub(crate) const fn id(self) -> u8 {
+        match self {
+            Aead::Aes256Gcm => 1,
+            Aead::ChaCha20Poly1305 => 2,
+            Aead::Aes128Gcm => 3,
+        }
+    }
+
+    pub(crate) fn from_id(id: u8) -> Result<Self> {
+        Aead::ALL
+            .into_iter()
+            .find(|aead| aead.id() == id)
+            .ok_or(Error::Format("unknown AEAD identifier"))
+    }
+}
+
+impl fmt::Display for Aead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Aead {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Aead::ALL
+            .into_iter()
+            .find(|aead| aead.name().eq_ignore_ascii_case(s))
+            .ok_or(Error::Format("unknown AEAD name"))
+    }
+}
+
+/// A keyed instance of one of the [`Aead`] algorithms.
+pub(crate) enum Cipher {
+    Aes256Gcm(Box<Aes256Gcm>),
+    ChaCha20Poly1305(Box<ChaCha20Poly1305>),
+    Aes128Gcm(Box<Aes128Gcm>),
+}
+
+impl Cipher {
+    /// Keys the algorithm with the first [`Aead::key_len`] bytes of `key`.
+    pub(crate) fn new(aead: Aead, key: &[u8; KEY_LEN]) -> Self {
+        match aead {
+            Aead::Aes256Gcm => Cipher::Aes256Gcm(Box::new(Aes256Gcm::new(key.into()))),
+            Aead::ChaCha20Poly1305 => {
+                Cipher::ChaCha20Poly1305(Box::new(ChaCha20Poly1305::new(key.into())))
+            }
+            Aead::Aes128Gcm => Cipher::Aes128Gcm(Box::new(Aes128Gcm::new(key[..16].into()))),
+        }
+    }
+
+    /// Encrypts `buffer` in place and appends the tag.
+    pub(crate) fn seal(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &mut Vec<u8>,
+    ) -> Result<()> {
+        let result = match self {
+            Cipher::Aes256Gcm(cipher) => cipher.encrypt_in_place(nonce.into(), aad, buffer),
+            Cipher::ChaCha20Poly1305(cipher) => cipher.encrypt_in_place(nonce.into(), aad, buffer),
+            Cipher::Aes128Gcm(cipher) => cipher.encrypt_in_place(nonce.into(), aad, buffer),
+        };
+        result.map_err(|_| Error::Format("plaintext too long for AEAD"))
+    }
+
+    /// Verifies and strips the tag, decrypting `buffer` in place.
+    pub(crate) fn open(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &mut Vec<u8>,
+    ) -> Result<()> {
+        let result = match self {
+            Cipher::Aes256Gcm(cipher) => cipher.decrypt_in_place(nonce.into(), aad, buffer),
+            Cipher::ChaCha20Poly1305(cipher) => cipher.decrypt_in_place(nonce.into(), aad, buffer),
+            Cipher::Aes128Gcm(cipher) => cipher.decrypt_in_place(nonce.into(), aad, buffer),
+        };
+        result.map_err(|_| Error::Decryption)
+    }
+}
@@ -0,0 +1,392 @@
+//! Mutually authenticated key exchange built from the McEliece KEM.
+//!
+//! Both parties hold static McEliece keypairs and know each other's public
+//! key in advance. Authentication is implicit in decapsulation: only the
+//! holder of a static secret key can recover what was encapsulated to it. An
+//! ephemeral keypair on the initiator's side adds forward secrecy.
+//!
+//! ```text
+//! Initiator (A)                                          Responder (B)
+//! (epk, esk) = generate()
+//! (ct_b, k_b) = encapsulate(pk_B)
+//!                         Message1 { epk, ct_b }  ->
+//!                                               k_b = decapsulate(ct_b, sk_B)
+//!                                               (ct_a, k_a) = encapsulate(pk_A)
+//!                                               (ct_e, k_e) = encapsulate(epk)
+//!                   <-  Message2 { ct_a, ct_e, tag_B }
+//! k_a = decapsulate(ct_a, sk_A)
+//! k_e = decapsulate(ct_e, esk)
+//! check tag_B
+//!                         Message3 { tag_A }  ->
+//!                                               check tag_A
+//! ```
+//!
+//! With `th = SHA3-256("mce-ake-v1" || pk_A || pk_B || epk || ct_b || ct_a ||
+//! ct_e)`, all keys come from `HKDF-SHA256(salt = th, ikm = k_a || k_b ||
+//! k_e)` under distinct labels: one transport key per direction and one
+//! confirmation key per party. Each confirmation tag is an HMAC-SHA256 of
+//! `th`, so a completed exchange proves both sides saw the same transcript.
+//!
+//! Generating the ephemeral keypair dominates the cost of a handshake;
+//! [`Initiator::start_with_ephemeral`] accepts one generated ahead of time,
+//! e.g. by a [`KeyPool`](crate::KeyPool). Never reuse an ephemeral keypair.
+
+use std::fmt;
+
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand::{CryptoRng, RngCore};
+use sha2::Sha256;
+use sha3::{Digest, Sha3_256};
+use zeroize::Zeroizing;
+
+use crate::error::{Error, Result};
+use crate::kem::{self, Ciphertext, PublicKey, SecretKey, SharedSecret};
+
+const TRANSCRIPT_LABEL: &[u8] = b"mce-ake-v1";
+const TAG_LEN: usize = 32;
+const KEY_LEN: usize = 32;
+
+/// First message, from initiator to responder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message1 {
+    ephemeral: PublicKey,
+    ciphertext: Ciphertext,
+}
+
+/// Second message, from responder to initiator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message2 {
+    to_initiator: Ciphertext,
+    to_ephemeral: Ciphertext,
+    tag: [u8; TAG_LEN],
+}
+
+/// Third message, from initiator to responder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message3 {
+    tag: [u8; TAG_LEN],
+}
+
+fn check_len(what: &'static str, bytes: &[u8], expected: usize) -> Result<()> {
+    if bytes.len() != expected {
+        return Err(Error::InvalidLength {
+            what,
+            expected,
+            actual: bytes.len(),
+        });
+    }
+    Ok(())
+}
+
+impl Message1 {
+    /// Encoded length in bytes.
+    pub const LEN: usize = PublicKey::LEN + Ciphertext::LEN;
+
+    /// Parses the `ephemeral public key || ciphertext` encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_len("handshake message 1", bytes, Self::LEN)?;
+        let (ephemeral, ciphertext) = bytes.split_at(PublicKey::LEN);
+        Ok(Message1 {
+            ephemeral: PublicKey::from_bytes(ephemeral)?,
+            ciphertext: Ciphertext::from_bytes(ciphertext)?,
+        })
+    }
+
+    /// Returns the `ephemeral public key || ciphertext` encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.ephemeral.as_bytes(), self.ciphertext.as_bytes()].concat()
+    }
+}
+
+impl Message2 {
+    /// Encoded length in bytes.
+    pub const LEN: usize = 2 * Ciphertext::LEN + TAG_LEN;
+
+    /// Parses the `ciphertext to initiator || ciphertext to ephemeral || tag`
+    /// encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_len("handshake message 2", bytes, Self::LEN)?;
+        let (to_initiator, rest) = bytes.split_at(Ciphertext::LEN);
+        let (to_ephemeral, tag) = rest.split_at(Ciphertext::LEN);
+        Ok(Message2 {
+            to_initiator: Ciphertext::from_bytes(to_initiator)?,
+            to_ephemeral: Ciphertext::from_bytes(to_ephemeral)?,
+            tag: tag.try_into().expect("length checked above"),
+        })
+    }
+
+    /// Returns the `ciphertext to initiator || ciphertext to ephemeral || tag`
+    /// encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            self.to_initiator.as_bytes(),
+            self.to_ephemeral.as_bytes(),
+            &self.tag,
+        ]
+        .concat()
+    }
+}
+
+impl Message3 {
+    /// Encoded length in bytes.
+    pub const LEN: usize = TAG_LEN;
+
+    /// Parses the tag.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_len("handshake message 3", bytes, Self::LEN)?;
+        Ok(Message3 {
+            tag: bytes.try_into().expect("length checked above"),
+        })
+    }
+
+    /// Returns the tag.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.tag.to_vec()
+    }
+}
+
+/// The keys of an established session.
+pub struct Session {
+    send: Zeroizing<[u8; KEY_LEN]>,
+    receive: Zeroizing<[u8; KEY_LEN]>,
+    transcript_hash: [u8; 32],
+}
+
+impl Session {
+    /// Key for traffic this party sends.
+    pub fn send_key(&self) -> &[u8; KEY_LEN] {
+        &self.send
+    }
+
+    /// Key for traffic this party receives.
+    pub fn receive_key(&self) -> &[u8; KEY_LEN] {
+        &self.receive
+    }
+
+    /// Hash of the handshake transcript, identical on both sides. Suitable
+    /// for channel binding.
+    pub fn transcript_hash(&self) -> &[u8; 32] {
+        &self.transcript_hash
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("transcript_hash", &hex::encode(self.transcript_hash))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Keys derived from the three shared secrets and the transcript.
+struct Schedule {
+    transcript_hash: [u8; 32],
+    initiator_to_responder: Zeroizing<[u8; KEY_LEN]>,
+    responder_to_initiator: Zeroizing<[u8; KEY_LEN]>,
+    initiator_confirm: Zeroizing<[u8; KEY_LEN]>,
+    responder_confirm: Zeroizing<[u8; KEY_LEN]>,
+}
+
+impl Schedule {
+    fn new(transcript: [&[u8]; 6], secrets: [&SharedSecret; 3]) -> Schedule {
+        let mut hash = Sha3_256::new().chain_update(TRANSCRIPT_LABEL);
+        for part in transcript {
+            hash.update(part);
+        }
+        let transcript_hash: [u8; 32] = hash.finalize().into();
+
+        let mut ikm = Zeroizing::new(Vec::with_capacity(3 * SharedSecret::LEN));
+        for secret in secrets {
+            ikm.extend_from_slice(secret.as_bytes());
+        }
+        let hkdf = Hkdf::<Sha256>::new(Some(&transcript_hash), &ikm);
+        let expand = |label: &[u8]| {
+            let mut key = Zeroizing::new([0u8; KEY_LEN]);
+            hkdf.expand(label, key.as_mut())
+                .expect("32 bytes is a valid HKDF-SHA256 output length");
+            key
+        };
+        Schedule {
+            transcript_hash,
+            initiator_to_responder: expand(b"mce-ake-v1 initiator to responder"),
+            responder_to_initiator: expand(b"mce-ake-v1 responder to initiator"),
+            initiator_confirm: expand(b"mce-ake-v1 initiator confirm"),
+            responder_confirm: expand(b"mce-ake-v1 responder confirm"),
+        }
+    }
+
+    fn tag(&self, key: &[u8; KEY_LEN]) -> [u8; TAG_LEN] {
+        mac(key, &self.transcript_hash)
+            .finalize()
+            .into_bytes()
+            .into()
+    }
+}
+
+fn mac(key: &[u8; KEY_LEN], transcript_hash: &[u8; 32]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key");
+    mac.update(transcript_hash);
+    mac
+}
+
+fn verify(key: &[u8; KEY_LEN], transcript_hash: &[u8; 32], tag: &[u8; TAG_LEN]) -> Result<()> {
+    mac(key, transcript_hash)
+        .verify_slice(tag)
+        .map_err(|_| Error::Confirmation)
+}
+
+/// The initiator's side of a handshake awaiting [`Message2`].
+pub struct Initiator<'a> {
+    public_key: &'a PublicKey,
+    secret_key: &'a SecretKey,
+    peer: &'a PublicKey,
+    ephemeral: (PublicKey, SecretKey),
+    to_peer: Ciphertext,
+    peer_secret: SharedSecret,
+}
+
+impl<'a> Initiator<'a> {
+    /// Starts a handshake with the holder of `peer`, generating a fresh
+    /// ephemeral keypair.
+    pub fn start<R: RngCore + CryptoRng>(
+        public_key: &'a PublicKey,
+        secret_key: &'a SecretKey,
+        peer: &'a PublicKey,
+        rng: &mut R,
+    ) -> Result<(Initiator<'a>, Message1)> {
+        let ephemeral = kem::generate(rng)?;
+        Self::start_with_ephemeral(public_key, secret_key, peer, ephemeral, rng)
+    }
+
+    /// Starts a handshake using a freshly generated, never used `ephemeral`
+    /// keypair.
+    pub fn start_with_ephemeral<R: RngCore + CryptoRng>(
+        public_key: &'a PublicKey,
+        secret_key: &'a SecretKey,
+        peer: &'a PublicKey,
+        ephemeral: (PublicKey, SecretKey),
+        rng: &mut R,
+    ) -> Result<(Initiator<'a>, Message1)> {
+        let (to_peer, peer_secret) = kem::encapsulate(peer, rng)?;
+        let message = Message1 {
+            ephemeral: ephemeral.0.clone(),
+            ciphertext: to_peer.clone(),
+        };
+        let initiator = Initiator {
+            public_key,
+            secret_key,
+            peer,
+            ephemeral,
+            to_peer,
+            peer_secret,
+        };
+        Ok((initiator, message))
+    }
+
+    /// Processes the responder's reply and authenticates it.
+    ///
+    /// Fails with [`Error::Confirmation`] if the responder does not hold the
+    /// secret key for `peer` or the messages were altered.
+    pub fn finish(self, message: &Message2) -> Result<(Session, Message3)> {
+        let own_secret = kem::decapsulate(&message.to_initiator, self.secret_key)?;
+        let ephemeral_secret = kem::decapsulate(&message.to_ephemeral, &self.ephemeral.1)?;
+        let schedule = Schedule::new(
+            [
+                self.public_key.as_bytes(),
+                self.peer.as_bytes(),
+                self.ephemeral.0.as_bytes(),
+                self.to_peer.as_bytes(),
+                message.to_initiator.as_bytes(),
+                message.to_ephemeral.as_bytes(),
+            ],
+            [&own_secret, &self.peer_secret, &ephemeral_secret],
+        );
+        verify(
+            &schedule.responder_confirm,
+            &schedule.transcript_hash,
+            &message.tag,
+        )?;
+        let reply = Message3 {
+            tag: schedule.tag(&schedule.initiator_confirm),
+        };
+        let session = Session {
+            send: schedule.initiator_to_responder,
+            receive: schedule.responder_to_initiator,
+            transcript_hash: schedule.transcript_hash,
+        };
+        Ok((session, reply))
+    }
+}
+
+impl fmt::Debug for Initiator<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Initiator").finish_non_exhaustive()
+    }
+}
+
+/// The responder's side of a handshake awaiting [`Message3`].
+pub struct Responder {
+    session: Session,
+    initiator_confirm: Zeroizing<[u8; KEY_LEN]>,
+}
+
+impl Responder {
+    /// Answers an initiator's first message. `peer` is the initiator's
+    /// static public key.
+    pub fn respond<R: RngCore + CryptoRng>(
+        public_key: &PublicKey,
+        secret_key: &SecretKey,
+        peer: &PublicKey,
+        message: &Message1,
+        rng: &mut R,
+    ) -> Result<(Responder, Message2)> {
+        let own_secret = kem::decapsulate(&message.ciphertext, secret_key)?;
+        let (to_initiator, peer_secret) = kem::encapsulate(peer, rng)?;
+        let (to_ephemeral, ephemeral_secret) = kem::encapsulate(&message.ephemeral, rng)?;
+        let schedule = Schedule::new(
+            [
+                peer.as_bytes(),
+                public_key.as_bytes(),
+                message.ephemeral.as_bytes(),
+                message.ciphertext.as_bytes(),
+                to_initiator.as_bytes(),
+                to_ephemeral.as_bytes(),
+            ],
+            [&peer_secret, &own_secret, &ephemeral_secret],
+        );
+        let reply = Message2 {
+            to_initiator,
+            to_ephemeral,
+            tag: schedule.tag(&schedule.responder_confirm),
+        };
+        let responder = Responder {
+            session: Session {
+                send: schedule.responder_to_initiator,
+                receive: schedule.initiator_to_responder,
+                transcript_hash: schedule.transcript_hash,
+            },
+            initiator_confirm: schedule.initiator_confirm,
+        };
+        Ok((responder, reply))
+    }
+
+    /// Authenticates the initiator's final message and completes the session.
+    ///
+    /// Fails with [`Error::Confirmation`] if the initiator does not hold the
+    /// secret key for the expected public key or the messages were altered.
+    pub fn finish(self, message: &Message3) -> Result<Session> {
+        verify(
+            &self.initiator_confirm,
+            &self.session.transcript_hash,
+            &message.tag,
+        )?;
+        Ok(self.session)
+    }
+}
+
+impl fmt::Debug for Responder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Responder").finish_non_exhaustive()
+    }
+}
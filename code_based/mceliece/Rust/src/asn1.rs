@@ -0,0 +1,100 @@
+//! SubjectPublicKeyInfo and PKCS#8 encodings for McEliece keys.
+//!
+//! Public keys are carried as the raw key in the `subjectPublicKey` BIT
+//! STRING of an X.509 `SubjectPublicKeyInfo`; secret keys as the raw key in
+//! the `privateKey` OCTET STRING of a PKCS#8 / RFC 5958 `OneAsymmetricKey`
+//! (version 1, no embedded public key). The `AlgorithmIdentifier` carries one
+//! OID per parameter set and no parameters.
+//!
+//! No OIDs have been assigned for Classic McEliece yet. Until they are, the
+//! provisional Bouncy Castle arc `1.3.6.1.4.1.22554.5.1.n` is used, which
+//! Bouncy Castle and several PQC toolkits already recognise.
+//!
+//! The [`EncodePublicKey`](spki::EncodePublicKey),
+//! [`DecodePublicKey`](spki::DecodePublicKey),
+//! [`EncodePrivateKey`](pkcs8::EncodePrivateKey) and
+//! [`DecodePrivateKey`](pkcs8::DecodePrivateKey) traits provide the DER and
+//! PEM conversions.
+
+use pkcs8::{ObjectIdentifier, PrivateKeyInfo, SecretDocument};
+use spki::der::asn1::BitStringRef;
+use spki::{AlgorithmIdentifierRef, Document, SubjectPublicKeyInfoRef};
+
+use crate::kem::{PublicKey, SecretKey};
+use crate::params::ParameterSet;
+
+const MCELIECE_ARC: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.22554.5.1");
+
+impl ParameterSet {
+    /// The object identifier naming this set in an `AlgorithmIdentifier`.
+    pub fn oid(self) -> ObjectIdentifier {
+        MCELIECE_ARC
+            .push_arc(self.id().into())
+            .expect("arc fits in an OID")
+    }
+
+    /// Looks up a parameter set by its [`oid`](ParameterSet::oid).
+    pub fn from_oid(oid: ObjectIdentifier) -> Option<ParameterSet> {
+        ParameterSet::ALL.into_iter().find(|set| set.oid() == oid)
+    }
+}
+
+fn algorithm(params: ParameterSet) -> AlgorithmIdentifierRef<'static> {
+    AlgorithmIdentifierRef {
+        oid: params.oid(),
+        parameters: None,
+    }
+}
+
+/// Checks that `algorithm` names the compiled parameter set without parameters.
+fn check_algorithm(algorithm: &AlgorithmIdentifierRef<'_>) -> spki::Result<()> {
+    match ParameterSet::from_oid(algorithm.oid) {
+        Some(set) if set.is_available() => {}
+        _ => return Err(spki::Error::OidUnknown { oid: algorithm.oid }),
+    }
+    if algorithm.parameters.is_some() {
+        return Err(spki::Error::KeyMalformed);
+    }
+    Ok(())
+}
+
+impl TryFrom<SubjectPublicKeyInfoRef<'_>> for PublicKey {
+    type Error = spki::Error;
+
+    fn try_from(spki: SubjectPublicKeyInfoRef<'_>) -> spki::Result<Self> {
+        check_algorithm(&spki.algorithm)?;
+        let bytes = spki
+            .subject_public_key
+            .as_bytes()
+            .ok_or(spki::Error::KeyMalformed)?;
+        PublicKey::from_bytes(bytes).map_err(|_| spki::Error::KeyMalformed)
+    }
+}
+
+impl spki::EncodePublicKey for PublicKey {
+    fn to_public_key_der(&self) -> spki::Result<Document> {
+        let spki = SubjectPublicKeyInfoRef {
+            algorithm: algorithm(self.parameter_set()),
+            subject_public_key: BitStringRef::from_bytes(self.as_bytes())?,
+        };
+        Document::try_from(&spki)
+    }
+}
+
+impl TryFrom<PrivateKeyInfo<'_>> for SecretKey {
+    type Error = pkcs8::Error;
+
+    fn try_from(info: PrivateKeyInfo<'_>) -> pkcs8::Result<Self> {
+        check_algorithm(&info.algorithm)?;
+        SecretKey::from_bytes(info.private_key).map_err(|_| pkcs8::Error::KeyMalformed)
+    }
+}
+
+impl pkcs8::EncodePrivateKey for SecretKey {
+    fn to_pkcs8_der(&self) -> pkcs8::Result<SecretDocument> {
+        SecretDocument::try_from(PrivateKeyInfo::new(
+            algorithm(self.parameter_set()),
+            self.as_bytes(),
+        ))
+    }
+}
@@ -0,0 +1,49 @@
+//! Encapsulation and decapsulation of many items in parallel.
+//!
+//! Work is spread across Rayon's global thread pool. Each encapsulation draws
+//! from its own ChaCha20 generator, seeded from the caller's RNG before the
+//! parallel section, so the result does not depend on scheduling.
+
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use rayon::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::error::Result;
+use crate::kem::{self, Ciphertext, PublicKey, SecretKey, SharedSecret};
+
+/// Encapsulates a fresh shared secret to each of `public_keys`.
+///
+/// Results are in the order of `public_keys`. Fails if any encapsulation
+/// fails.
+pub fn encapsulate_many<R: RngCore + CryptoRng>(
+    public_keys: &[PublicKey],
+    rng: &mut R,
+) -> Result<Vec<(Ciphertext, SharedSecret)>> {
+    let seeds: Vec<Zeroizing<[u8; 32]>> = public_keys
+        .iter()
+        .map(|_| {
+            let mut seed = Zeroizing::new([0u8; 32]);
+            rng.fill_bytes(&mut *seed);
+            seed
+        })
+        .collect();
+    public_keys
+        .par_iter()
+        .zip(seeds)
+        .map(|(public_key, seed)| kem::encapsulate(public_key, &mut ChaCha20Rng::from_seed(*seed)))
+        .collect()
+}
+
+/// Recovers the shared secret of each of `ciphertexts` under `secret_key`.
+///
+/// Results are in the order of `ciphertexts`.
+pub fn decapsulate_many(
+    ciphertexts: &[Ciphertext],
+    secret_key: &SecretKey,
+) -> Result<Vec<SharedSecret>> {
+    ciphertexts
+        .par_iter()
+        .map(|ciphertext| kem::decapsulate(ciphertext, secret_key))
+        .collect()
+}
@@ -0,0 +1,92 @@
+//! The deterministic random bit generator of the NIST PQC reference code.
+
+use std::fmt;
+
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes256;
+use rand::{CryptoRng, RngCore};
+
+/// The AES-256-CTR DRBG behind `randombytes` in the NIST PQC reference code
+/// (`rng.c`), without prediction resistance or a personalization string.
+///
+/// Seeded with a known 48-byte entropy input it reproduces the randomness the
+/// reference implementations consume, so keys and ciphertexts can be compared
+/// with KAT files and other implementations. Its whole output is determined
+/// by the seed: use it for testing and debugging, not for real keys.
+pub struct NistDrbg {
+    key: [u8; 32],
+    v: [u8; 16],
+}
+
+impl NistDrbg {
+    /// Equivalent to `randombytes_init(entropy_input, NULL, 256)`.
+    pub fn new(entropy_input: &[u8; 48]) -> Self {
+        let mut drbg = NistDrbg {
+            key: [0; 32],
+            v: [0; 16],
+        };
+        drbg.update(Some(entropy_input));
+        drbg
+    }
+
+    fn increment(&mut self) {
+        self.v = u128::from_be_bytes(self.v).wrapping_add(1).to_be_bytes();
+    }
+
+    fn block(&mut self) -> [u8; 16] {
+        self.increment();
+        let mut block = self.v.into();
+        Aes256::new(&self.key.into()).encrypt_block(&mut block);
+        block.into()
+    }
+
+    /// `AES256_CTR_DRBG_Update`.
+    fn update(&mut self, provided_data: Option<&[u8; 48]>) {
+        let mut temp = [0u8; 48];
+        for chunk in temp.chunks_exact_mut(16) {
+            chunk.copy_from_slice(&self.block());
+        }
+        if let Some(data) = provided_data {
+            temp.iter_mut().zip(data).for_each(|(t, d)| *t ^= d);
+        }
+        self.key.copy_from_slice(&temp[..32]);
+        self.v.copy_from_slice(&temp[32..]);
+    }
+}
+
+impl RngCore for NistDrbg {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    /// `randombytes`: one call produces `dest.len()` bytes and then updates
+    /// the state, so splitting a request changes the output.
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(16) {
+            let block = self.block();
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+        self.update(None);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for NistDrbg {}
+
+impl fmt::Debug for NistDrbg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NistDrbg").field(&"-- redacted --").finish()
+    }
+}
@@ -0,0 +1,96 @@
+//! Error type returned by the `mce` library.
+
+use std::{fmt, io};
+
+use crate::encoding::Kind;
+use crate::params::ParameterSet;
+
+/// Errors produced by the key encapsulation API.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A byte string did not have the length required for the object being built.
+    InvalidLength {
+        what: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    /// The parameter set is valid but this build was compiled for another one.
+    UnsupportedParameterSet(ParameterSet),
+    /// A string did not name any Classic McEliece parameter set.
+    UnknownParameterSet(String),
+    /// An X25519 exchange produced the all-zero output of a low-order point.
+    LowOrderPoint,
+    /// Input did not follow the expected encoding.
+    Format(&'static str),
+    /// An encoded object holds a different kind of object than requested.
+    WrongKind { expected: Kind, found: Kind },
+    /// An encoded object's checksum does not match its contents.
+    Checksum,
+    /// Authenticated decryption failed: wrong key or tampered data.
+    Decryption,
+    /// A key confirmation tag did not match.
+    Confirmation,
+    /// A derived key was requested with an unsupported length.
+    InvalidKeyLength(usize),
+    /// A protocol state machine was used out of order or misconfigured.
+    Protocol(&'static str),
+    /// Reading or writing a stream failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidLength {
+                what,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "invalid {what} length: expected {expected} bytes, got {actual}"
+            ),
+            Error::UnsupportedParameterSet(set) => write!(
+                f,
+                "parameter set {set} is not available in this build (compiled for {})",
+                ParameterSet::compiled()
+            ),
+            Error::UnknownParameterSet(name) => write!(f, "unknown parameter set {name:?}"),
+            Error::LowOrderPoint => f.write_str("X25519 public key is a low-order point"),
+            Error::Format(reason) => write!(f, "malformed input: {reason}"),
+            Error::WrongKind { expected, found } => {
+                write!(f, "expected an encoded {expected}, found a {found}")
+            }
+            Error::Checksum => f.write_str("checksum mismatch: the data is corrupted"),
+            Error::Decryption => f.write_str("decryption failed: wrong key or corrupted data"),
+            Error::Confirmation => {
+                f.write_str("key confirmation failed: the peer has a different shared secret")
+            }
+            Error::InvalidKeyLength(len) => write!(
+                f,
+                "cannot derive a {len}-byte key: lengths from 1 to {} bytes are supported",
+                crate::kdf::MAX_KEY_LEN
+            ),
+            Error::Protocol(reason) => write!(f, "protocol error: {reason}"),
+            Error::Io(err) => write!(f, "I/O error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// Convenience alias used throughout the crate.
+pub type Result<T> = std::result::Result<T, Error>;
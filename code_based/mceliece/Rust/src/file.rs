@@ -0,0 +1,353 @@
+//! KEM-DEM public-key encryption of arbitrary-length streams.
+//!
+//! A fresh McEliece encapsulation to the recipient's public key yields a
+//! shared secret, which HKDF-SHA256 turns into a one-time AEAD key. The
+//! payload is then encrypted in fixed-size chunks following the STREAM
+//! construction, so files of any size can be processed in constant memory and
+//! truncation or reordering of chunks is detected.
+//!
+//! ```text
+//! header:  "MCEF" | version (1) | aead id (1) | chunk size (u32 BE)
+//!          | ciphertext length (u16 BE) | KEM ciphertext
+//! chunks:  AEAD(key, nonce_i, aad = header, plaintext_i)
+//! nonce_i: 0x000000 | i (u64 BE) | 0x01 on the final chunk, else 0x00
+//! ```
+//!
+//! Every chunk but the last carries exactly `chunk size` bytes of plaintext;
+//! the last one carries the remainder and may be empty. The whole header is
+//! the associated data of every chunk, so tampering with it fails decryption.
+//!
+//! [`encrypt_to_many`] writes version 2, which encrypts the payload once
+//! under a random content key and wraps that key for each recipient:
+//!
+//! ```text
+//! header:  "MCEF" | version (2) | aead id (1) | chunk size (u32 BE)
+//!          | ciphertext length (u16 BE) | recipient count (u16 BE)
+//!          | per recipient: fingerprint (32) | KEM ciphertext | wrapped key (48)
+//! wrapped: AEAD(HKDF-SHA256(shared secret, "mce-file-v2 wrap key"),
+//!               nonce = 0, aad = fingerprint | KEM ciphertext, content key)
+//! ```
+//!
+//! The fingerprint is the recipient key's [`Fingerprint`]. Chunks are as in
+//! version 1, under the content key. [`decrypt`] reads both versions.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use hkdf::Hkdf;
+use rand::{CryptoRng, RngCore};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::aead::{Aead, Cipher, KEY_LEN, NONCE_LEN, TAG_LEN};
+use crate::error::{Error, Result};
+use crate::fingerprint::Fingerprint;
+use crate::kem::{self, Ciphertext, PublicKey, SecretKey, SharedSecret};
+
+const MAGIC: &[u8; 4] = b"MCEF";
+const VERSION: u8 = 1;
+const VERSION_MULTI: u8 = 2;
+const KDF_INFO: &[u8] = b"mce-file-v1 payload key";
+const WRAP_INFO: &[u8] = b"mce-file-v2 wrap key";
+const FINGERPRINT_LEN: usize = 32;
+const WRAPPED_KEY_LEN: usize = KEY_LEN + TAG_LEN;
+
+/// Plaintext bytes per chunk written by [`encrypt`].
+pub const DEFAULT_CHUNK_SIZE: u32 = 64 * 1024;
+/// Largest chunk size [`decrypt`] accepts, bounding its memory use.
+const MAX_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Length of the fixed part of the header, before the KEM ciphertext or, in
+/// version 2, the recipient count.
+const FIXED_HEADER_LEN: usize = 4 + 1 + 1 + 4 + 2;
+/// Length of one recipient entry in a version 2 header.
+const RECIPIENT_LEN: usize = FINGERPRINT_LEN + Ciphertext::LEN + WRAPPED_KEY_LEN;
+
+/// Encrypts everything read from `input` to `public_key`, writing the
+/// encrypted file to `output`. Returns the number of plaintext bytes.
+pub fn encrypt<R, W, G>(
+    public_key: &PublicKey,
+    aead: Aead,
+    input: R,
+    output: W,
+    rng: &mut G,
+) -> Result<u64>
+where
+    R: Read,
+    W: Write,
+    G: RngCore + CryptoRng,
+{
+    let (ciphertext, shared_secret) = kem::encapsulate(public_key, rng)?;
+    let header = encode_header(aead, DEFAULT_CHUNK_SIZE, &ciphertext);
+    let cipher = Cipher::new(aead, &payload_key(&shared_secret));
+    seal_payload(&cipher, &header, input, output)
+}
+
+/// Encrypts everything read from `input` once, so that the holder of any of
+/// the `recipients`' secret keys can decrypt it. Returns the number of
+/// plaintext bytes.
+///
+/// Fails with [`Error::Format`] if there are no recipients or more than
+/// 65535.
+pub fn encrypt_to_many<R, W, G>(
+    recipients: &[PublicKey],
+    aead: Aead,
+    input: R,
+    output: W,
+    rng: &mut G,
+) -> Result<u64>
+where
+    R: Read,
+    W: Write,
+    G: RngCore + CryptoRng,
+{
+    let count = u16::try_from(recipients.len())
+        .ok()
+        .filter(|&count| count > 0)
+        .ok_or(Error::Format("between 1 and 65535 recipients are required"))?;
+    let mut content_key = Zeroizing::new([0u8; KEY_LEN]);
+    rng.fill_bytes(&mut *content_key);
+
+    let mut header = Vec::with_capacity(FIXED_HEADER_LEN + 2 + recipients.len() * RECIPIENT_LEN);
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION_MULTI);
+    header.push(aead.id());
+    header.extend_from_slice(&DEFAULT_CHUNK_SIZE.to_be_bytes());
+    header.extend_from_slice(&(Ciphertext::LEN as u16).to_be_bytes());
+    header.extend_from_slice(&count.to_be_bytes());
+    for public_key in recipients {
+        let (ciphertext, shared_secret) = kem::encapsulate(public_key, rng)?;
+        let entry_start = header.len();
+        header.extend_from_slice(public_key.fingerprint().as_bytes());
+        header.extend_from_slice(ciphertext.as_bytes());
+        // Room for the tag, so sealing in place never reallocates and leaves
+        // a copy of the key behind.
+        let mut wrapped = Vec::with_capacity(KEY_LEN + TAG_LEN);
+        wrapped.extend_from_slice(&*content_key);
+        Cipher::new(aead, &wrap_key(&shared_secret)).seal(
+            &[0; NONCE_LEN],
+            &header[entry_start..],
+            &mut wrapped,
+        )?;
+        header.extend_from_slice(&wrapped);
+    }
+
+    let cipher = Cipher::new(aead, &content_key);
+    seal_payload(&cipher, &header, input, output)
+}
+
+/// Writes `header` followed by the payload read from `input` as chunks.
+fn seal_payload<R: Read, W: Write>(
+    cipher: &Cipher,
+    header: &[u8],
+    input: R,
+    mut output: W,
+) -> Result<u64> {
+    output.write_all(header)?;
+    let mut input = BufReader::new(input);
+    let mut buffer = Vec::with_capacity(DEFAULT_CHUNK_SIZE as usize + TAG_LEN);
+    let mut total = 0u64;
+    for counter in 0u64.. {
+        buffer.clear();
+        let read = (&mut input)
+            .take(DEFAULT_CHUNK_SIZE.into())
+            .read_to_end(&mut buffer)?;
+        total += read as u64;
+        let last = read < DEFAULT_CHUNK_SIZE as usize || input.fill_buf()?.is_empty();
+        cipher.seal(&chunk_nonce(counter, last), header, &mut buffer)?;
+        output.write_all(&buffer)?;
+        if last {
+            break;
+        }
+    }
+    output.flush()?;
+    Ok(total)
+}
+
+/// Decrypts a file produced by [`encrypt`] or [`encrypt_to_many`], writing
+/// the plaintext to `output`. Returns the number of plaintext bytes.
+///
+/// For a multi-recipient file each wrapped key is tried in turn, as the
+/// secret key alone does not reveal which entry is its own; the file fails
+/// with [`Error::Decryption`] if none unwraps.
+///
+/// Chunks are written as soon as they authenticate. On error, `output` may
+/// already hold a prefix of the plaintext and should be discarded.
+pub fn decrypt<R, W>(secret_key: &SecretKey, input: R, mut output: W) -> Result<u64>
+where
+    R: Read,
+    W: Write,
+{
+    let mut input = BufReader::new(input);
+    let (header, aead, chunk_size, keys) = read_header(&mut input)?;
+    let key = match keys {
+        Keys::Single(ciphertext) => payload_key(&kem::decapsulate(&ciphertext, secret_key)?),
+        Keys::Wrapped { offset, count } => {
+            unwrap_content_key(aead, &header[offset..], count, secret_key)?
+        }
+    };
+    let cipher = Cipher::new(aead, &key);
+
+    let sealed_len = chunk_size as usize + TAG_LEN;
+    let mut buffer = Vec::with_capacity(sealed_len);
+    let mut total = 0u64;
+    for counter in 0u64.. {
+        buffer.clear();
+        let read = (&mut input)
+            .take(sealed_len as u64)
+            .read_to_end(&mut buffer)?;
+        let last = read < sealed_len || input.fill_buf()?.is_empty();
+        if read < TAG_LEN {
+            return Err(Error::Format("truncated chunk"));
+        }
+        cipher.open(&chunk_nonce(counter, last), &header, &mut buffer)?;
+        total += buffer.len() as u64;
+        output.write_all(&buffer)?;
+        if last {
+            break;
+        }
+    }
+    output.flush()?;
+    Ok(total)
+}
+
+fn encode_header(aead: Aead, chunk_size: u32, ciphertext: &Ciphertext) -> Vec<u8> {
+    let ct = ciphertext.as_bytes();
+    let mut header = Vec::with_capacity(FIXED_HEADER_LEN + ct.len());
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION);
+    header.push(aead.id());
+    header.extend_from_slice(&chunk_size.to_be_bytes());
+    header.extend_from_slice(&(ct.len() as u16).to_be_bytes());
+    header.extend_from_slice(ct);
+    header
+}
+
+/// How a file's payload key is obtained.
+enum Keys {
+    /// Version 1: a single KEM ciphertext.
+    Single(Ciphertext),
+    /// Version 2: `count` recipient entries starting at `offset` in the
+    /// header.
+    Wrapped { offset: usize, count: usize },
+}
+
+/// Reads and validates the header, returning its raw bytes alongside the
+/// decoded fields.
+fn read_header<R: Read>(input: &mut R) -> Result<(Vec<u8>, Aead, u32, Keys)> {
+    let mut header = vec![0u8; FIXED_HEADER_LEN];
+    read_exact_or_format(input, &mut header)?;
+    if &header[..4] != MAGIC {
+        return Err(Error::Format("not an mce encrypted file"));
+    }
+    let version = header[4];
+    if version != VERSION && version != VERSION_MULTI {
+        return Err(Error::Format("unsupported encrypted file version"));
+    }
+    let aead = Aead::from_id(header[5])?;
+    let chunk_size = u32::from_be_bytes(header[6..10].try_into().expect("4 bytes"));
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+        return Err(Error::Format("invalid chunk size"));
+    }
+    let ct_len = u16::from_be_bytes(header[10..12].try_into().expect("2 bytes")) as usize;
+    if ct_len != Ciphertext::LEN {
+        return Err(Error::InvalidLength {
+            what: "ciphertext",
+            expected: Ciphertext::LEN,
+            actual: ct_len,
+        });
+    }
+
+    if version == VERSION {
+        header.resize(FIXED_HEADER_LEN + ct_len, 0);
+        read_exact_or_format(input, &mut header[FIXED_HEADER_LEN..])?;
+        let ciphertext = Ciphertext::from_bytes(&header[FIXED_HEADER_LEN..])?;
+        return Ok((header, aead, chunk_size, Keys::Single(ciphertext)));
+    }
+
+    header.resize(FIXED_HEADER_LEN + 2, 0);
+    read_exact_or_format(input, &mut header[FIXED_HEADER_LEN..])?;
+    let count = u16::from_be_bytes(header[FIXED_HEADER_LEN..].try_into().expect("2 bytes"));
+    if count == 0 {
+        return Err(Error::Format("encrypted file has no recipients"));
+    }
+    let offset = header.len();
+    header.resize(offset + usize::from(count) * RECIPIENT_LEN, 0);
+    read_exact_or_format(input, &mut header[offset..])?;
+    let keys = Keys::Wrapped {
+        offset,
+        count: count.into(),
+    };
+    Ok((header, aead, chunk_size, keys))
+}
+
+/// Finds the recipient entry `secret_key` can unwrap and returns the content
+/// key.
+fn unwrap_content_key(
+    aead: Aead,
+    entries: &[u8],
+    count: usize,
+    secret_key: &SecretKey,
+) -> Result<Zeroizing<[u8; KEY_LEN]>> {
+    for entry in entries.chunks_exact(RECIPIENT_LEN).take(count) {
+        let (bound, wrapped) = entry.split_at(FINGERPRINT_LEN + Ciphertext::LEN);
+        let ciphertext = Ciphertext::from_bytes(&bound[FINGERPRINT_LEN..])?;
+        // Decapsulating someone else's ciphertext succeeds with an unrelated
+        // secret, so only the wrapped key's tag identifies our entry.
+        let shared_secret = kem::decapsulate(&ciphertext, secret_key)?;
+        let mut key = Zeroizing::new(wrapped.to_vec());
+        if Cipher::new(aead, &wrap_key(&shared_secret))
+            .open(&[0; NONCE_LEN], bound, &mut key)
+            .is_ok()
+        {
+            let mut content_key = Zeroizing::new([0u8; KEY_LEN]);
+            content_key.copy_from_slice(&key);
+            return Ok(content_key);
+        }
+    }
+    Err(Error::Decryption)
+}
+
+/// Fingerprints of the recipients of a multi-recipient file, in header
+/// order. Version 1 files name no recipients and yield an empty list.
+pub fn recipients<R: Read>(mut input: R) -> Result<Vec<Fingerprint>> {
+    let (header, _, _, keys) = read_header(&mut input)?;
+    Ok(match keys {
+        Keys::Single(_) => Vec::new(),
+        Keys::Wrapped { offset, .. } => header[offset..]
+            .chunks_exact(RECIPIENT_LEN)
+            .map(|entry| {
+                Fingerprint::from_bytes(entry[..FINGERPRINT_LEN].try_into().expect("32 bytes"))
+            })
+            .collect(),
+    })
+}
+
+fn read_exact_or_format<R: Read>(input: &mut R, buf: &mut [u8]) -> Result<()> {
+    input.read_exact(buf).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => Error::Format("truncated header"),
+        _ => Error::Io(err),
+    })
+}
+
+fn payload_key(shared_secret: &SharedSecret) -> Zeroizing<[u8; KEY_LEN]> {
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    Hkdf::<Sha256>::new(None, shared_secret.as_bytes())
+        .expand(KDF_INFO, &mut *key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+fn wrap_key(shared_secret: &SharedSecret) -> Zeroizing<[u8; KEY_LEN]> {
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    Hkdf::<Sha256>::new(None, shared_secret.as_bytes())
+        .expand(WRAP_INFO, &mut *key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+fn chunk_nonce(counter: u64, last: bool) -> [u8; NONCE_LEN] {
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce[3..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = u8::from(last);
+    nonce
+}
@@ -0,0 +1,167 @@
+//! Fingerprints for verifying public keys out of band.
+//!
+//! A public key is hundreds of kilobytes, far too much to compare by eye. Its
+//! fingerprint is
+//!
+//! ```text
+//! SHA3-256("mce-fingerprint-v1" || parameter set id || public key)
+//! ```
+//!
+//! with the parameter set's one-byte [`id`](crate::ParameterSet::id), so the
+//! same bytes under two parameter sets never share a fingerprint. It can be
+//! rendered as hex, as unpadded RFC 4648 base32, or as one word per byte from
+//! a fixed list of 256 English words. Reading the first
+//! [`SAS_WORDS`] words aloud, the short authentication string, checks 64
+//! bits of the fingerprint, which is enough to catch a substituted key during
+//! a phone call but not to name a key permanently.
+
+use std::fmt;
+use std::str::FromStr;
+
+use sha3::{Digest, Sha3_256};
+
+use crate::error::{Error, Result};
+use crate::kem::PublicKey;
+use crate::params::ParameterSet;
+
+const LABEL: &[u8] = b"mce-fingerprint-v1";
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Words in a short authentication string.
+pub const SAS_WORDS: usize = 8;
+
+/// One word per byte value.
+const WORDS: [&str; 256] = [
+    "acid", "acorn", "actor", "adobe", "agent", "album", "alert", "alley", "almond", "amber",
+    "anchor", "angle", "ankle", "apple", "apron", "arch", "arena", "arrow", "aspen", "atlas",
+    "attic", "autumn", "axis", "bacon", "badge", "bagel", "baker", "bamboo", "banana", "banjo",
+    "barley", "barn", "basil", "basket", "beacon", "bean", "beaver", "bell", "bench", "berry",
+    "birch", "bishop", "bison", "blade", "blanket", "boat", "bonnet", "border", "bottle", "branch",
+    "bread", "brick", "bridge", "bronze", "brook", "bubble", "bucket", "bugle", "butter", "button",
+    "cabin", "cactus", "camel", "camera", "candle", "canoe", "canyon", "carbon", "carpet",
+    "carrot", "castle", "cedar", "cellar", "cement", "cherry", "chess", "cider", "circle",
+    "citrus", "clock", "cloud", "clover", "cobalt", "coffee", "comet", "copper", "coral", "cotton",
+    "cougar", "crane", "crater", "crystal", "daisy", "dancer", "delta", "denim", "desert",
+    "dinner", "dolphin", "domino", "donkey", "dragon", "drum", "eagle", "easel", "echo", "elbow",
+    "elm", "ember", "engine", "fabric", "falcon", "fennel", "ferry", "fiddle", "finch", "flame",
+    "flute", "forest", "fossil", "fox", "frost", "galaxy", "garden", "garlic", "geyser", "ginger",
+    "globe", "goblet", "gopher", "grape", "gravel", "guitar", "hammer", "harbor", "harp", "hazel",
+    "helmet", "heron", "honey", "hornet", "igloo", "indigo", "iris", "island", "ivory", "jacket",
+    "jaguar", "jelly", "jigsaw", "jungle", "kayak", "kernel", "kettle", "kiwi", "koala", "ladder",
+    "lagoon", "laurel", "lemon", "lentil", "lily", "linen", "lizard", "locket", "lotus", "magnet",
+    "mango", "maple", "marble", "meadow", "melon", "meteor", "mitten", "mosaic", "muffin",
+    "napkin", "nectar", "needle", "nickel", "noodle", "nutmeg", "oasis", "ocean", "olive", "onion",
+    "orange", "orchid", "otter", "oyster", "paddle", "palace", "panda", "paper", "parrot",
+    "pebble", "pepper", "piano", "pigeon", "pillow", "pine", "planet", "plum", "pocket", "pony",
+    "poppy", "potato", "prism", "puzzle", "quartz", "quill", "quilt", "rabbit", "radish", "raven",
+    "ribbon", "river", "robin", "rocket", "rose", "ruby", "saddle", "salmon", "scarf", "shadow",
+    "shell", "silver", "spider", "spruce", "statue", "stone", "sunset", "tablet", "teapot",
+    "tiger", "timber", "tomato", "topaz", "tulip", "turtle", "valley", "velvet", "violin", "wagon",
+    "walnut", "walrus", "willow", "window", "winter", "wizard", "yacht", "yarrow", "yogurt",
+    "zebra", "zephyr", "zinc",
+];
+
+/// The SHA3-256 fingerprint of a McEliece public key.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fingerprint([u8; 32]);
+
+impl Fingerprint {
+    /// Fingerprints a public key of the compiled parameter set.
+    pub fn of(public_key: &PublicKey) -> Fingerprint {
+        Fingerprint(
+            Sha3_256::new()
+                .chain_update(LABEL)
+                .chain_update([ParameterSet::compiled().id()])
+                .chain_update(public_key.as_bytes())
+                .finalize()
+                .into(),
+        )
+    }
+
+    /// Wraps a fingerprint stored elsewhere.
+    pub const fn from_bytes(bytes: [u8; 32]) -> Fingerprint {
+        Fingerprint(bytes)
+    }
+
+    /// The raw 32 bytes.
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Lowercase hex, 64 characters. Also the [`Display`](fmt::Display) form.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// Unpadded RFC 4648 base32, 52 characters.
+    pub fn to_base32(&self) -> String {
+        let mut out = String::with_capacity(52);
+        let (mut buffer, mut bits) = (0u16, 0);
+        for &byte in &self.0 {
+            buffer = (buffer << 8) | u16::from(byte);
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                out.push(BASE32_ALPHABET[usize::from((buffer >> bits) & 31)] as char);
+            }
+        }
+        if bits > 0 {
+            out.push(BASE32_ALPHABET[usize::from((buffer << (5 - bits)) & 31)] as char);
+        }
+        out
+    }
+
+    /// All 32 bytes as space-separated words.
+    pub fn to_words(&self) -> String {
+        self.words(self.0.len())
+    }
+
+    /// The first [`SAS_WORDS`] bytes as space-separated words.
+    pub fn short_authentication_string(&self) -> String {
+        self.words(SAS_WORDS)
+    }
+
+    fn words(&self, count: usize) -> String {
+        self.0[..count]
+            .iter()
+            .map(|&byte| WORDS[usize::from(byte)])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl PublicKey {
+    /// The key's [`Fingerprint`].
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::of(self)
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl fmt::Debug for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fingerprint({self})")
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = Error;
+
+    /// Parses the hex form, ignoring case and any `:` or whitespace between
+    /// digits.
+    fn from_str(s: &str) -> Result<Self> {
+        let digits: String = s
+            .chars()
+            .filter(|c| *c != ':' && !c.is_whitespace())
+            .collect();
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(digits, &mut bytes)
+            .map_err(|_| Error::Format("a fingerprint is 64 hex digits"))?;
+        Ok(Fingerprint(bytes))
+    }
+}
@@ -0,0 +1,341 @@
+//! Hybrid Public Key Encryption (RFC 9180) with Classic McEliece as the KEM.
+//!
+//! The key schedule, encryption contexts and secret export follow RFC 9180
+//! exactly, with HKDF-SHA256 as the KDF and a choice of AES-128-GCM,
+//! AES-256-GCM or ChaCha20-Poly1305 as the AEAD. The KEM's `enc` is the
+//! McEliece ciphertext and its shared secret is used as HPKE's
+//! `shared_secret` directly, as for other KEMs that are not built on
+//! Diffie-Hellman.
+//!
+//! Classic McEliece has no registered HPKE KEM identifier. This module uses
+//! the provisional identifier `0xFF00` plus the parameter set's
+//! [`id`](crate::ParameterSet::id), e.g. `0xFF01` for mceliece348864, so
+//! envelopes only interoperate with other users of this crate until an
+//! identifier is assigned.
+//!
+//! Only the base and PSK modes are provided. The auth and auth-PSK modes
+//! authenticate the sender through the KEM's `AuthEncap`, which needs a KEM
+//! with sender key pairs such as DHKEM; a McEliece encapsulation cannot prove
+//! who produced it. Combine the PSK mode with a pre-shared key, or a
+//! signature over the envelope, when the sender must be authenticated.
+//!
+//! ```no_run
+//! use mce::hpke::{self, Mode};
+//! use mce::Aead;
+//!
+//! let mut rng = rand::thread_rng();
+//! let (public_key, secret_key) = mce::generate(&mut rng)?;
+//! let (enc, ciphertext) =
+//!     hpke::seal(Aead::Aes128Gcm, Mode::Base, &public_key, b"info", b"aad", b"hi", &mut rng)?;
+//! let plaintext =
+//!     hpke::open(Aead::Aes128Gcm, Mode::Base, &enc, &secret_key, b"info", b"aad", &ciphertext)?;
+//! assert_eq!(plaintext, b"hi");
+//! # Ok::<(), mce::Error>(())
+//! ```
+
+use std::fmt;
+
+use hkdf::Hkdf;
+use rand::{CryptoRng, RngCore};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::aead::{Aead, Cipher, KEY_LEN, NONCE_LEN};
+use crate::error::{Error, Result};
+use crate::kem::{self, Ciphertext, PublicKey, SecretKey, SharedSecret};
+use crate::params::ParameterSet;
+
+/// HKDF-SHA256's identifier in the HPKE KDF registry.
+const KDF_ID: u16 = 0x0001;
+/// Output length of SHA-256.
+const HASH_LEN: usize = 32;
+/// Shortest pre-shared key accepted, as recommended by RFC 9180.
+pub const MIN_PSK_LEN: usize = 32;
+/// Longest secret [`SenderContext::export`] can produce.
+pub const MAX_EXPORT_LEN: usize = 255 * HASH_LEN;
+
+/// The provisional HPKE KEM identifier for the compiled parameter set.
+pub fn kem_id() -> u16 {
+    0xFF00 | u16::from(ParameterSet::compiled().id())
+}
+
+/// An HPKE mode and its inputs.
+#[derive(Clone, Copy)]
+pub enum Mode<'a> {
+    /// `mode_base`: encryption to the recipient's public key alone.
+    Base,
+    /// `mode_psk`: the sender and recipient also share a secret key, which
+    /// authenticates the sender as a holder of it.
+    Psk {
+        /// The pre-shared key, at least [`MIN_PSK_LEN`] bytes.
+        psk: &'a [u8],
+        /// A non-empty identifier for the pre-shared key.
+        psk_id: &'a [u8],
+    },
+}
+
+impl Mode<'_> {
+    fn id(self) -> u8 {
+        match self {
+            Mode::Base => 0x00,
+            Mode::Psk { .. } => 0x01,
+        }
+    }
+}
+
+impl fmt::Debug for Mode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Base => f.write_str("Base"),
+            Mode::Psk { psk_id, .. } => f
+                .debug_struct("Psk")
+                .field("psk_id", &hex::encode(psk_id))
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+fn suite_id(aead: Aead) -> [u8; 10] {
+    let mut id = *b"HPKE\0\0\0\0\0\0";
+    id[4..6].copy_from_slice(&kem_id().to_be_bytes());
+    id[6..8].copy_from_slice(&KDF_ID.to_be_bytes());
+    id[8..10].copy_from_slice(&aead.hpke_id().to_be_bytes());
+    id
+}
+
+/// `LabeledExtract` from RFC 9180, section 4.
+fn labeled_extract(suite_id: &[u8], salt: &[u8], label: &[u8], ikm: &[u8]) -> Hkdf<Sha256> {
+    let labeled_ikm = Zeroizing::new([b"HPKE-v1", suite_id, label, ikm].concat());
+    Hkdf::<Sha256>::new(Some(salt), &labeled_ikm)
+}
+
+/// `LabeledExpand` from RFC 9180, section 4, into `out`.
+fn labeled_expand(prk: &Hkdf<Sha256>, suite_id: &[u8], label: &[u8], info: &[u8], out: &mut [u8]) {
+    let len = u16::try_from(out.len())
+        .expect("HPKE output lengths fit in two bytes")
+        .to_be_bytes();
+    prk.expand_multi_info(&[&len, b"HPKE-v1", suite_id, label, info], out)
+        .expect("output length checked by the caller");
+}
+
+/// `LabeledExtract` with an empty salt, returning the pseudorandom key.
+fn labeled_hash(suite_id: &[u8], label: &[u8], ikm: &[u8]) -> [u8; HASH_LEN] {
+    let labeled_ikm = [b"HPKE-v1", suite_id, label, ikm].concat();
+    Hkdf::<Sha256>::extract(Some(&[]), &labeled_ikm).0.into()
+}
+
+/// State shared by sender and receiver contexts.
+struct Context {
+    cipher: Cipher,
+    base_nonce: [u8; NONCE_LEN],
+    sequence: u64,
+    exporter: Hkdf<Sha256>,
+    suite_id: [u8; 10],
+}
+
+/// The HPKE key schedule, RFC 9180 section 5.1.
+fn key_schedule(
+    aead: Aead,
+    mode: Mode<'_>,
+    shared_secret: &SharedSecret,
+    info: &[u8],
+) -> Result<Context> {
+    let (psk, psk_id): (&[u8], &[u8]) = match mode {
+        Mode::Base => (&[], &[]),
+        Mode::Psk { psk, psk_id } => {
+            if psk.len() < MIN_PSK_LEN {
+                return Err(Error::Protocol("HPKE pre-shared key shorter than 32 bytes"));
+            }
+            if psk_id.is_empty() {
+                return Err(Error::Protocol("HPKE pre-shared key identifier is empty"));
+            }
+            (psk, psk_id)
+        }
+    };
+    let suite_id = suite_id(aead);
+
+    let mut context = vec![mode.id()];
+    context.extend_from_slice(&labeled_hash(&suite_id, b"psk_id_hash", psk_id));
+    context.extend_from_slice(&labeled_hash(&suite_id, b"info_hash", info));
+    let secret = labeled_extract(&suite_id, shared_secret.as_bytes(), b"secret", psk);
+
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    labeled_expand(
+        &secret,
+        &suite_id,
+        b"key",
+        &context,
+        &mut key[..aead.key_len()],
+    );
+    let mut base_nonce = [0u8; NONCE_LEN];
+    labeled_expand(&secret, &suite_id, b"base_nonce", &context, &mut base_nonce);
+    let mut exporter_secret = Zeroizing::new([0u8; HASH_LEN]);
+    labeled_expand(
+        &secret,
+        &suite_id,
+        b"exp",
+        &context,
+        exporter_secret.as_mut(),
+    );
+
+    Ok(Context {
+        cipher: Cipher::new(aead, &key),
+        base_nonce,
+        sequence: 0,
+        exporter: Hkdf::<Sha256>::from_prk(exporter_secret.as_ref())
+            .expect("the exporter secret is one hash long"),
+        suite_id,
+    })
+}
+
+impl Context {
+    fn nonce(&self) -> Result<[u8; NONCE_LEN]> {
+        if self.sequence == u64::MAX {
+            return Err(Error::Protocol("HPKE context message limit reached"));
+        }
+        let mut nonce = self.base_nonce;
+        for (byte, seq) in nonce[4..].iter_mut().zip(self.sequence.to_be_bytes()) {
+            *byte ^= seq;
+        }
+        Ok(nonce)
+    }
+
+    fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = self.nonce()?;
+        let mut buffer = plaintext.to_vec();
+        self.cipher.seal(&nonce, aad, &mut buffer)?;
+        self.sequence += 1;
+        Ok(buffer)
+    }
+
+    fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = self.nonce()?;
+        let mut buffer = ciphertext.to_vec();
+        self.cipher.open(&nonce, aad, &mut buffer)?;
+        self.sequence += 1;
+        Ok(buffer)
+    }
+
+    fn export(&self, exporter_context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>> {
+        if len == 0 || len > MAX_EXPORT_LEN {
+            return Err(Error::InvalidKeyLength(len));
+        }
+        let mut secret = Zeroizing::new(vec![0u8; len]);
+        labeled_expand(
+            &self.exporter,
+            &self.suite_id,
+            b"sec",
+            exporter_context,
+            &mut secret,
+        );
+        Ok(secret)
+    }
+}
+
+/// The sender's encryption context, from [`setup_sender`].
+pub struct SenderContext(Context);
+
+/// The recipient's decryption context, from [`setup_receiver`].
+pub struct ReceiverContext(Context);
+
+impl SenderContext {
+    /// Encrypts the next message in sequence.
+    pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.0.seal(aad, plaintext)
+    }
+
+    /// Derives a secret of `len` bytes, at most [`MAX_EXPORT_LEN`], from the
+    /// context. The recipient derives the same secret.
+    pub fn export(&self, exporter_context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>> {
+        self.0.export(exporter_context, len)
+    }
+}
+
+impl ReceiverContext {
+    /// Decrypts the next message in sequence.
+    ///
+    /// Fails with [`Error::Decryption`] if the message is not the next one
+    /// the sender sealed or was altered; the sequence does not advance.
+    pub fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.0.open(aad, ciphertext)
+    }
+
+    /// Derives the same secret as [`SenderContext::export`].
+    pub fn export(&self, exporter_context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>> {
+        self.0.export(exporter_context, len)
+    }
+}
+
+impl fmt::Debug for SenderContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SenderContext")
+            .field("sequence", &self.0.sequence)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for ReceiverContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReceiverContext")
+            .field("sequence", &self.0.sequence)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encapsulates to `public_key` and sets up a sender context, returning the
+/// encapsulation `enc` the recipient needs.
+pub fn setup_sender<R: RngCore + CryptoRng>(
+    aead: Aead,
+    mode: Mode<'_>,
+    public_key: &PublicKey,
+    info: &[u8],
+    rng: &mut R,
+) -> Result<(Ciphertext, SenderContext)> {
+    let (enc, shared_secret) = kem::encapsulate(public_key, rng)?;
+    let context = key_schedule(aead, mode, &shared_secret, info)?;
+    Ok((enc, SenderContext(context)))
+}
+
+/// Decapsulates `enc` and sets up the matching receiver context.
+pub fn setup_receiver(
+    aead: Aead,
+    mode: Mode<'_>,
+    enc: &Ciphertext,
+    secret_key: &SecretKey,
+    info: &[u8],
+) -> Result<ReceiverContext> {
+    let shared_secret = kem::decapsulate(enc, secret_key)?;
+    Ok(ReceiverContext(key_schedule(
+        aead,
+        mode,
+        &shared_secret,
+        info,
+    )?))
+}
+
+/// Single-shot encryption of one message to `public_key`.
+pub fn seal<R: RngCore + CryptoRng>(
+    aead: Aead,
+    mode: Mode<'_>,
+    public_key: &PublicKey,
+    info: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+    rng: &mut R,
+) -> Result<(Ciphertext, Vec<u8>)> {
+    let (enc, mut context) = setup_sender(aead, mode, public_key, info, rng)?;
+    Ok((enc, context.seal(aad, plaintext)?))
+}
+
+/// Single-shot decryption of a message from [`seal`].
+pub fn open(
+    aead: Aead,
+    mode: Mode<'_>,
+    enc: &Ciphertext,
+    secret_key: &SecretKey,
+    info: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>> {
+    setup_receiver(aead, mode, enc, secret_key, info)?.open(aad, ciphertext)
+}
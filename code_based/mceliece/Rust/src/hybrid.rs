@@ -0,0 +1,280 @@
+//! Hybrid Classic McEliece + X25519 key encapsulation.
+//!
+//! The hybrid shared secret is derived from both a McEliece encapsulation and
+//! an ephemeral-static X25519 exchange, so an attacker has to break both
+//! primitives to learn it. The two component secrets are combined with
+//!
+//! ```text
+//! SHA3-256("mce-hybrid-x25519-v1" || ss_mceliece || ss_x25519
+//!          || ct_mceliece || ct_x25519 || pk_x25519)
+//! ```
+//!
+//! Binding both ciphertexts and the recipient's X25519 key ties the combined
+//! secret to a single encapsulation.
+//!
+//! Keys and ciphertexts are encoded as the McEliece part followed by the
+//! 32-byte X25519 part.
+
+use std::fmt;
+
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha3::{Digest, Sha3_256};
+use subtle::ConstantTimeEq;
+use x25519_dalek::{EphemeralSecret, StaticSecret};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::error::{Error, Result};
+use crate::kem::{self, SharedSecret};
+use crate::params::ParameterSet;
+
+const COMBINER_LABEL: &[u8] = b"mce-hybrid-x25519-v1";
+
+/// Length of every X25519 component.
+const X25519_LEN: usize = 32;
+
+/// A hybrid public key: a McEliece public key plus an X25519 public key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey {
+    mceliece: kem::PublicKey,
+    x25519: x25519_dalek::PublicKey,
+}
+
+/// A hybrid secret key: a McEliece secret key plus an X25519 secret.
+pub struct SecretKey {
+    mceliece: kem::SecretKey,
+    x25519: StaticSecret,
+    x25519_public: x25519_dalek::PublicKey,
+}
+
+/// A hybrid ciphertext: a McEliece ciphertext plus an ephemeral X25519 public key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ciphertext {
+    mceliece: kem::Ciphertext,
+    x25519: [u8; X25519_LEN],
+}
+
+/// Splits `bytes` into a McEliece part of `head` bytes and the trailing X25519 part.
+fn split<'a>(
+    what: &'static str,
+    bytes: &'a [u8],
+    head: usize,
+) -> Result<(&'a [u8], [u8; X25519_LEN])> {
+    if bytes.len() != head + X25519_LEN {
+        return Err(Error::InvalidLength {
+            what,
+            expected: head + X25519_LEN,
+            actual: bytes.len(),
+        });
+    }
+    let (head, tail) = bytes.split_at(head);
+    Ok((head, tail.try_into().expect("length checked above")))
+}
+
+impl PublicKey {
+    /// Encoded length in bytes.
+    pub const LEN: usize = kem::PublicKey::LEN + X25519_LEN;
+
+    /// Parses the `mceliece || x25519` encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (mceliece, x25519) = split("hybrid public key", bytes, kem::PublicKey::LEN)?;
+        Ok(PublicKey {
+            mceliece: kem::PublicKey::from_bytes(mceliece)?,
+            x25519: x25519.into(),
+        })
+    }
+
+    /// Returns the `mceliece || x25519` encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.mceliece.as_bytes(), self.x25519.as_bytes()].concat()
+    }
+
+    /// The McEliece component.
+    pub fn mceliece(&self) -> &kem::PublicKey {
+        &self.mceliece
+    }
+
+    /// The parameter set of the McEliece component.
+    pub fn parameter_set(&self) -> ParameterSet {
+        self.mceliece.parameter_set()
+    }
+}
+
+impl SecretKey {
+    /// Encoded length in bytes.
+    pub const LEN: usize = kem::SecretKey::LEN + X25519_LEN;
+
+    /// Parses the `mceliece || x25519` encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (mceliece, x25519) = split("hybrid secret key", bytes, kem::SecretKey::LEN)?;
+        let x25519 = StaticSecret::from(x25519);
+        Ok(SecretKey {
+            mceliece: kem::SecretKey::from_bytes(mceliece)?,
+            x25519_public: x25519_dalek::PublicKey::from(&x25519),
+            x25519,
+        })
+    }
+
+    /// Returns the `mceliece || x25519` encoding, zeroed when dropped.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new([self.mceliece.as_bytes(), self.x25519.as_bytes()].concat())
+    }
+
+    /// The McEliece component.
+    pub fn mceliece(&self) -> &kem::SecretKey {
+        &self.mceliece
+    }
+
+    /// The public key matching this secret key's X25519 component.
+    pub fn x25519_public(&self) -> &x25519_dalek::PublicKey {
+        &self.x25519_public
+    }
+
+    /// The parameter set of the McEliece component.
+    pub fn parameter_set(&self) -> ParameterSet {
+        self.mceliece.parameter_set()
+    }
+}
+
+/// Compares in constant time.
+impl PartialEq for SecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        let mceliece = self.mceliece.as_bytes().ct_eq(other.mceliece.as_bytes());
+        (mceliece & self.x25519.as_bytes().ct_eq(other.x25519.as_bytes())).into()
+    }
+}
+
+impl Eq for SecretKey {}
+
+impl Zeroize for SecretKey {
+    fn zeroize(&mut self) {
+        self.mceliece.zeroize();
+        self.x25519.zeroize();
+    }
+}
+
+// Both components zero themselves when dropped.
+impl ZeroizeOnDrop for SecretKey {}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SecretKey").field(&"-- redacted --").finish()
+    }
+}
+
+impl Ciphertext {
+    /// Encoded length in bytes.
+    pub const LEN: usize = kem::Ciphertext::LEN + X25519_LEN;
+
+    /// Parses the `mceliece || x25519` encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (mceliece, x25519) = split("hybrid ciphertext", bytes, kem::Ciphertext::LEN)?;
+        Ok(Ciphertext {
+            mceliece: kem::Ciphertext::from_bytes(mceliece)?,
+            x25519,
+        })
+    }
+
+    /// Returns the `mceliece || x25519` encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.mceliece.as_bytes(), &self.x25519].concat()
+    }
+
+    /// The parameter set of the McEliece component.
+    pub fn parameter_set(&self) -> ParameterSet {
+        self.mceliece.parameter_set()
+    }
+}
+
+/// Generates a hybrid keypair for the compiled McEliece parameter set.
+pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Result<(PublicKey, SecretKey)> {
+    let (mceliece_public, mceliece_secret) = kem::generate(rng)?;
+    let x25519 = StaticSecret::random_from_rng(&mut *rng);
+    let x25519_public = x25519_dalek::PublicKey::from(&x25519);
+    Ok((
+        PublicKey {
+            mceliece: mceliece_public,
+            x25519: x25519_public,
+        },
+        SecretKey {
+            mceliece: mceliece_secret,
+            x25519,
+            x25519_public,
+        },
+    ))
+}
+
+/// Deterministically derives a hybrid keypair from `seed`; see
+/// [`crate::generate_from_seed`].
+pub fn generate_from_seed(seed: &[u8; 32]) -> Result<(PublicKey, SecretKey)> {
+    generate(&mut ChaCha20Rng::from_seed(*seed))
+}
+
+/// Encapsulates a hybrid shared secret to `public_key`.
+///
+/// Fails with [`Error::LowOrderPoint`] if the recipient's X25519 key would
+/// make the exchange non-contributory.
+pub fn encapsulate<R: RngCore + CryptoRng>(
+    public_key: &PublicKey,
+    rng: &mut R,
+) -> Result<(Ciphertext, SharedSecret)> {
+    let (mceliece_ct, mceliece_ss) = kem::encapsulate(&public_key.mceliece, rng)?;
+
+    let ephemeral = EphemeralSecret::random_from_rng(&mut *rng);
+    let ephemeral_public = x25519_dalek::PublicKey::from(&ephemeral);
+    let x25519_ss = ephemeral.diffie_hellman(&public_key.x25519);
+    if !x25519_ss.was_contributory() {
+        return Err(Error::LowOrderPoint);
+    }
+
+    let ciphertext = Ciphertext {
+        mceliece: mceliece_ct,
+        x25519: ephemeral_public.to_bytes(),
+    };
+    let shared_secret = combine(
+        &mceliece_ss,
+        x25519_ss.as_bytes(),
+        &ciphertext,
+        &public_key.x25519,
+    );
+    Ok((ciphertext, shared_secret))
+}
+
+/// Recovers the hybrid shared secret carried by `ciphertext`.
+///
+/// Fails with [`Error::LowOrderPoint`] if the ephemeral X25519 key in the
+/// ciphertext is a low-order point.
+pub fn decapsulate(ciphertext: &Ciphertext, secret_key: &SecretKey) -> Result<SharedSecret> {
+    let mceliece_ss = kem::decapsulate(&ciphertext.mceliece, &secret_key.mceliece)?;
+
+    let x25519_ss = secret_key
+        .x25519
+        .diffie_hellman(&x25519_dalek::PublicKey::from(ciphertext.x25519));
+    if !x25519_ss.was_contributory() {
+        return Err(Error::LowOrderPoint);
+    }
+
+    Ok(combine(
+        &mceliece_ss,
+        x25519_ss.as_bytes(),
+        ciphertext,
+        &secret_key.x25519_public,
+    ))
+}
+
+fn combine(
+    mceliece_ss: &SharedSecret,
+    x25519_ss: &[u8; X25519_LEN],
+    ciphertext: &Ciphertext,
+    recipient: &x25519_dalek::PublicKey,
+) -> SharedSecret {
+    let digest = Sha3_256::new()
+        .chain_update(COMBINER_LABEL)
+        .chain_update(mceliece_ss.as_bytes())
+        .chain_update(x25519_ss)
+        .chain_update(ciphertext.mceliece.as_bytes())
+        .chain_update(ciphertext.x25519)
+        .chain_update(recipient.as_bytes())
+        .finalize();
+    SharedSecret::from_array(digest.into())
+}
@@ -0,0 +1,152 @@
+//! Deriving several session keys from one shared secret.
+//!
+//! A single encapsulation yields one 32-byte secret; [`derive_keys`] turns it
+//! into any number of independent keys of chosen lengths. The public key and
+//! ciphertext are bound into the derivation, so the keys belong to exactly one
+//! encapsulation to one recipient:
+//!
+//! ```text
+//! salt  = H("mce-kdf-v1 transcript" || len(pk) || pk || len(ct) || ct)
+//! prk   = HKDF-Extract(salt, shared secret)
+//! key_i = HKDF-Expand(prk, "mce-kdf-v1" || len(info) || info
+//!                          || len(context) || context || i || len_i, len_i)
+//! ```
+//!
+//! `H` is the KDF's hash, and every `len` and the key index `i` are big-endian
+//! u32s. Each key is expanded separately, so changing one requested length
+//! does not change the others. `info` names the application or protocol,
+//! `context` carries per-session data such as identities.
+
+use hkdf::hmac::digest::core_api::BlockSizeUser;
+use hkdf::hmac::digest::Digest;
+use hkdf::SimpleHkdf;
+use sha2::Sha256;
+use sha3::Sha3_256;
+use zeroize::Zeroizing;
+
+use crate::error::{Error, Result};
+use crate::hybrid;
+use crate::kem::{Ciphertext, PublicKey, SharedSecret};
+
+const TRANSCRIPT_LABEL: &[u8] = b"mce-kdf-v1 transcript";
+const EXPAND_LABEL: &[u8] = b"mce-kdf-v1";
+
+/// Largest key HKDF with a 32-byte hash can produce.
+pub const MAX_KEY_LEN: usize = 255 * 32;
+
+/// The hash underlying HKDF.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Kdf {
+    /// HKDF with HMAC-SHA-256 (RFC 5869).
+    #[default]
+    HkdfSha256,
+    /// HKDF with HMAC-SHA3-256.
+    HkdfSha3_256,
+}
+
+/// Derives one key per entry of `lengths` from a McEliece encapsulation.
+///
+/// Fails with [`Error::InvalidKeyLength`] if a length is zero or above
+/// [`MAX_KEY_LEN`].
+pub fn derive_keys(
+    kdf: Kdf,
+    shared_secret: &SharedSecret,
+    public_key: &PublicKey,
+    ciphertext: &Ciphertext,
+    info: &[u8],
+    context: &[u8],
+    lengths: &[usize],
+) -> Result<Vec<Zeroizing<Vec<u8>>>> {
+    let transcript = [public_key.as_bytes(), ciphertext.as_bytes()];
+    derive(kdf, shared_secret, transcript, info, context, lengths)
+}
+
+/// Derives one key per entry of `lengths` from a hybrid encapsulation; see
+/// [`derive_keys`].
+pub fn derive_keys_hybrid(
+    kdf: Kdf,
+    shared_secret: &SharedSecret,
+    public_key: &hybrid::PublicKey,
+    ciphertext: &hybrid::Ciphertext,
+    info: &[u8],
+    context: &[u8],
+    lengths: &[usize],
+) -> Result<Vec<Zeroizing<Vec<u8>>>> {
+    let transcript = [&public_key.to_bytes()[..], &ciphertext.to_bytes()[..]];
+    derive(kdf, shared_secret, transcript, info, context, lengths)
+}
+
+fn derive(
+    kdf: Kdf,
+    shared_secret: &SharedSecret,
+    [public_key, ciphertext]: [&[u8]; 2],
+    info: &[u8],
+    context: &[u8],
+    lengths: &[usize],
+) -> Result<Vec<Zeroizing<Vec<u8>>>> {
+    if let Some(&len) = lengths.iter().find(|&&len| len == 0 || len > MAX_KEY_LEN) {
+        return Err(Error::InvalidKeyLength(len));
+    }
+    let expand = match kdf {
+        Kdf::HkdfSha256 => expand_all::<Sha256>,
+        Kdf::HkdfSha3_256 => expand_all::<Sha3_256>,
+    };
+    Ok(expand(
+        shared_secret,
+        public_key,
+        ciphertext,
+        info,
+        context,
+        lengths,
+    ))
+}
+
+fn len_prefix(bytes: &[u8]) -> [u8; 4] {
+    u32::try_from(bytes.len())
+        .expect("input shorter than 4 GiB")
+        .to_be_bytes()
+}
+
+fn expand_all<H>(
+    shared_secret: &SharedSecret,
+    public_key: &[u8],
+    ciphertext: &[u8],
+    info: &[u8],
+    context: &[u8],
+    lengths: &[usize],
+) -> Vec<Zeroizing<Vec<u8>>>
+where
+    H: Digest + BlockSizeUser + Clone,
+{
+    let salt = H::new()
+        .chain_update(TRANSCRIPT_LABEL)
+        .chain_update(len_prefix(public_key))
+        .chain_update(public_key)
+        .chain_update(len_prefix(ciphertext))
+        .chain_update(ciphertext)
+        .finalize();
+    let hkdf = SimpleHkdf::<H>::new(Some(&salt), shared_secret.as_bytes());
+
+    let prefix = [
+        EXPAND_LABEL,
+        &len_prefix(info),
+        info,
+        &len_prefix(context),
+        context,
+    ]
+    .concat();
+    lengths
+        .iter()
+        .enumerate()
+        .map(|(i, &len)| {
+            let index = u32::try_from(i)
+                .expect("fewer than 2^32 keys")
+                .to_be_bytes();
+            let len_bytes = u32::try_from(len).expect("length checked").to_be_bytes();
+            let mut key = Zeroizing::new(vec![0u8; len]);
+            hkdf.expand_multi_info(&[&prefix, &index, &len_bytes], &mut key)
+                .expect("length checked");
+            key
+        })
+        .collect()
+}
@@ -0,0 +1,278 @@
+//! Owned key, ciphertext and shared-secret types and the KEM operations on them.
+
+use std::fmt;
+
+use classic_mceliece_rust as backend;
+use classic_mceliece_rust::{
+    CRYPTO_BYTES, CRYPTO_CIPHERTEXTBYTES, CRYPTO_PUBLICKEYBYTES, CRYPTO_SECRETKEYBYTES,
+};
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::error::{Error, Result};
+use crate::params::ParameterSet;
+
+/// A Classic McEliece public key.
+pub struct PublicKey(backend::PublicKey<'static>);
+
+/// A Classic McEliece secret key. Zeroed by the backend when dropped.
+pub struct SecretKey(backend::SecretKey<'static>);
+
+/// The ciphertext sent from the encapsulating party to the key owner.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Ciphertext([u8; CRYPTO_CIPHERTEXTBYTES]);
+
+/// The secret agreed on by both parties. Kept on the heap and zeroed when
+/// dropped.
+pub struct SharedSecret(Box<[u8; CRYPTO_BYTES]>);
+
+/// Copies `bytes` into a heap-allocated array without staging it on the stack.
+fn boxed_array<const N: usize>(what: &'static str, bytes: &[u8]) -> Result<Box<[u8; N]>> {
+    if bytes.len() != N {
+        return Err(Error::InvalidLength {
+            what,
+            expected: N,
+            actual: bytes.len(),
+        });
+    }
+    Ok(bytes
+        .to_vec()
+        .into_boxed_slice()
+        .try_into()
+        .expect("length checked above"))
+}
+
+impl PublicKey {
+    /// Encoded length in bytes.
+    pub const LEN: usize = CRYPTO_PUBLICKEYBYTES;
+
+    /// Builds a public key from its raw encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let buf = boxed_array::<CRYPTO_PUBLICKEYBYTES>("public key", bytes)?;
+        Ok(PublicKey::from_boxed(buf))
+    }
+
+    /// Takes ownership of a buffer holding the raw encoding.
+    pub(crate) fn from_boxed(buf: Box<[u8; CRYPTO_PUBLICKEYBYTES]>) -> Self {
+        PublicKey(backend::PublicKey::from(buf))
+    }
+
+    /// Returns the raw encoding of the key.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+
+    /// The parameter set the key belongs to.
+    pub fn parameter_set(&self) -> ParameterSet {
+        ParameterSet::compiled()
+    }
+}
+
+impl Clone for PublicKey {
+    fn clone(&self) -> Self {
+        PublicKey(self.0.to_owned())
+    }
+}
+
+impl PartialEq for PublicKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for PublicKey {}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PublicKey")
+            .field(&format_args!("{}...", hex::encode(&self.as_bytes()[..16])))
+            .finish()
+    }
+}
+
+impl SecretKey {
+    /// Encoded length in bytes.
+    pub const LEN: usize = CRYPTO_SECRETKEYBYTES;
+
+    /// Builds a secret key from its raw encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let buf = boxed_array::<CRYPTO_SECRETKEYBYTES>("secret key", bytes)?;
+        Ok(SecretKey(backend::SecretKey::from(buf)))
+    }
+
+    /// Returns the raw encoding of the key.
+    ///
+    /// Copying these bytes out of the `SecretKey` defeats the zeroing on drop.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+
+    /// The parameter set the key belongs to.
+    pub fn parameter_set(&self) -> ParameterSet {
+        ParameterSet::compiled()
+    }
+}
+
+/// Compares in constant time.
+impl PartialEq for SecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes().ct_eq(other.as_bytes()).into()
+    }
+}
+
+impl Eq for SecretKey {}
+
+impl Zeroize for SecretKey {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+// The backend key zeroes itself when dropped.
+impl ZeroizeOnDrop for SecretKey {}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SecretKey").field(&"-- redacted --").finish()
+    }
+}
+
+impl Ciphertext {
+    /// Encoded length in bytes.
+    pub const LEN: usize = CRYPTO_CIPHERTEXTBYTES;
+
+    /// Builds a ciphertext from its raw encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let array = bytes.try_into().map_err(|_| Error::InvalidLength {
+            what: "ciphertext",
+            expected: Self::LEN,
+            actual: bytes.len(),
+        })?;
+        Ok(Ciphertext(array))
+    }
+
+    /// Returns the raw encoding of the ciphertext.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The parameter set the ciphertext belongs to.
+    pub fn parameter_set(&self) -> ParameterSet {
+        ParameterSet::compiled()
+    }
+}
+
+impl fmt::Debug for Ciphertext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Ciphertext")
+            .field(&hex::encode(self.0))
+            .finish()
+    }
+}
+
+impl SharedSecret {
+    /// Length of the shared secret in bytes.
+    pub const LEN: usize = CRYPTO_BYTES;
+
+    /// Returns the secret bytes.
+    pub fn as_bytes(&self) -> &[u8; CRYPTO_BYTES] {
+        &self.0
+    }
+
+    /// Wraps a secret computed outside the McEliece KEM, e.g. by a combiner.
+    pub(crate) fn from_array(bytes: [u8; CRYPTO_BYTES]) -> Self {
+        SharedSecret(Box::new(bytes))
+    }
+
+    /// Copies a secret into a new heap buffer.
+    fn copy_of(bytes: &[u8; CRYPTO_BYTES]) -> Self {
+        let mut secret = SharedSecret(Box::new([0; CRYPTO_BYTES]));
+        secret.0.copy_from_slice(bytes);
+        secret
+    }
+}
+
+impl ConstantTimeEq for SharedSecret {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
+}
+
+/// Compares in constant time.
+impl PartialEq for SharedSecret {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SharedSecret {}
+
+impl Zeroize for SharedSecret {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SharedSecret {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SharedSecret {}
+
+impl fmt::Debug for SharedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedSecret")
+            .field(&"-- redacted --")
+            .finish()
+    }
+}
+
+/// Generates a fresh keypair for the compiled parameter set.
+pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Result<(PublicKey, SecretKey)> {
+    let (public_key, secret_key) = backend::keypair_boxed(rng);
+    Ok((PublicKey(public_key), SecretKey(secret_key)))
+}
+
+/// Deterministically derives a keypair for the compiled parameter set from
+/// `seed`.
+///
+/// The seed keys a ChaCha20 stream that stands in for the random source of
+/// [`generate`], so the same seed always yields the same keypair and the
+/// public key can be regenerated instead of stored. The seed is as sensitive
+/// as the secret key itself.
+///
+/// Reproducibility holds for a given parameter set and version of the
+/// `classic-mceliece-rust` backend; a backend that consumes randomness
+/// differently will derive different keys from the same seed.
+pub fn generate_from_seed(seed: &[u8; 32]) -> Result<(PublicKey, SecretKey)> {
+    generate(&mut ChaCha20Rng::from_seed(*seed))
+}
+
+/// Encapsulates a new shared secret to `public_key`.
+///
+/// The returned ciphertext is sent to the key owner, who recovers the same
+/// secret with [`decapsulate`].
+pub fn encapsulate<R: RngCore + CryptoRng>(
+    public_key: &PublicKey,
+    rng: &mut R,
+) -> Result<(Ciphertext, SharedSecret)> {
+    let (ciphertext, shared_secret) = backend::encapsulate_boxed(&public_key.0, rng);
+    Ok((
+        Ciphertext(*ciphertext.as_array()),
+        SharedSecret::copy_of(shared_secret.as_array()),
+    ))
+}
+
+/// Recovers the shared secret carried by `ciphertext`.
+///
+/// Classic McEliece uses implicit rejection: a malformed ciphertext yields an
+/// unrelated pseudorandom secret rather than an error.
+pub fn decapsulate(ciphertext: &Ciphertext, secret_key: &SecretKey) -> Result<SharedSecret> {
+    let ciphertext = backend::Ciphertext::from(ciphertext.0);
+    let shared_secret = backend::decapsulate_boxed(&ciphertext, &secret_key.0);
+    Ok(SharedSecret::copy_of(shared_secret.as_array()))
+}
@@ -0,0 +1,80 @@
+//! Classic McEliece key encapsulation.
+//!
+//! A thin, owned wrapper around [`classic_mceliece_rust`]: keys, ciphertexts and
+//! shared secrets are heap-allocated `'static` values that can be stored, sent
+//! between threads and rebuilt from bytes. Secret keys and shared secrets are
+//! zeroed when dropped, compare in constant time and are redacted in `Debug`
+//! output. [`SharedSecret`] implements [`subtle::ConstantTimeEq`], and
+//! [`confirm`] lets both parties check they derived the same secret.
+//! [`kdf::derive_keys`] expands a shared secret into several session keys
+//! bound to the public key and ciphertext.
+//!
+//! ```no_run
+//! let mut rng = rand::thread_rng();
+//! let (public_key, secret_key) = mce::generate(&mut rng)?;
+//! let (ciphertext, alice) = mce::encapsulate(&public_key, &mut rng)?;
+//! let bob = mce::decapsulate(&ciphertext, &secret_key)?;
+//! assert_eq!(alice.as_bytes(), bob.as_bytes());
+//! # Ok::<(), mce::Error>(())
+//! ```
+//!
+//! The Classic McEliece parameter set is chosen when the crate is built, via
+//! one of the `mceliece*` Cargo features (default `mceliece348864`).
+//! [`ParameterSet`] describes all of them, so sizes can be reported and
+//! foreign artifacts recognised, but keys and ciphertexts always use
+//! [`ParameterSet::compiled`]. [`generate_from_seed`] derives the same
+//! keypair every time from a 32-byte seed, and [`NistDrbg`] reproduces the
+//! randomness of the NIST reference implementation for known-answer tests.
+//! [`KeyPool`] generates keypairs on background threads ahead of demand, and
+//! [`encapsulate_many`] and [`decapsulate_many`] spread batches across cores.
+//!
+//! The [`hybrid`] module combines McEliece with X25519 for deployments that
+//! want classical security to hold even if the post-quantum KEM falls, and
+//! [`file`](mod@file) uses the KEM to encrypt whole files. [`ake`] runs a
+//! mutually authenticated key exchange between two holders of static keys,
+//! and [`noise`] runs post-quantum Noise handshakes with McEliece as the KEM.
+//! [`hpke`] provides RFC 9180 envelopes with McEliece as the HPKE KEM.
+//!
+//! Keys and ciphertexts have a raw form (`from_bytes`/`as_bytes`) and a
+//! versioned, checksummed form (`from_encoded`/`to_encoded`) for storage; see
+//! [`encoding`]. Public and secret keys also implement the `spki` and `pkcs8`
+//! encoding traits for interoperability with PKI tooling, and secret keys can
+//! be stored encrypted under a passphrase with [`passphrase`]. Public keys are
+//! compared out of band through their [`fingerprint`], and [`PublicKeyReader`]
+//! parses them incrementally as they arrive over a connection.
+
+mod aead;
+pub mod ake;
+mod asn1;
+mod batch;
+pub mod confirm;
+mod drbg;
+pub mod encoding;
+mod error;
+pub mod file;
+pub mod fingerprint;
+pub mod hpke;
+pub mod hybrid;
+pub mod kdf;
+mod kem;
+pub mod noise;
+mod params;
+pub mod passphrase;
+mod pool;
+mod reader;
+
+pub use aead::Aead;
+pub use batch::{decapsulate_many, encapsulate_many};
+pub use drbg::NistDrbg;
+pub use error::{Error, Result};
+pub use kem::{
+    decapsulate, encapsulate, generate, generate_from_seed, Ciphertext, PublicKey, SecretKey,
+    SharedSecret,
+};
+pub use params::ParameterSet;
+pub use pkcs8;
+pub use pool::KeyPool;
+pub use reader::PublicKeyReader;
+pub use spki;
+pub use subtle;
+pub use zeroize;
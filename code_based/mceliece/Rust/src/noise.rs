@@ -0,0 +1,515 @@
+//! Noise handshakes with Classic McEliece in place of Diffie-Hellman.
+//!
+//! This follows the post-quantum Noise framework (PQNoise, Schwabe, Stebila
+//! and Wiggers), in which every DH token of a Noise pattern is replaced by a
+//! KEM operation:
+//!
+//! - `e`: the sender generates an ephemeral keypair and sends the public key.
+//! - `ekem`: the sender encapsulates to the peer's ephemeral key, sends the
+//!   ciphertext and mixes the shared secret into the chaining key.
+//! - `s`: the sender sends its static public key, encrypted once a key exists.
+//! - `skem`: like `ekem`, but to the peer's static key; the ciphertext is
+//!   encrypted once a key exists.
+//!
+//! The supported patterns are
+//!
+//! ```text
+//! pqNN:                pqXX:                pqIK:
+//!   -> e                 -> e                 <- s
+//!   <- ekem              <- ekem, s           ...
+//!                        -> skem, s           -> skem, e, s
+//!                        <- skem              <- ekem, skem
+//! ```
+//!
+//! The protocol name is `Noise_<pattern>_<parameter set>_ChaChaPoly_SHA256`,
+//! e.g. `Noise_pqXX_mceliece348864_ChaChaPoly_SHA256`, and the symmetric
+//! state, cipher states and `Split()` are exactly those of the Noise
+//! specification (revision 34).
+//!
+//! # Message sizes
+//!
+//! Noise limits every message to 65535 bytes. Classic McEliece public keys
+//! are 261120 bytes or more, so handshake messages carrying `e` or `s` far
+//! exceed that limit, and this module does not enforce it during the
+//! handshake: frame handshake messages with a length prefix of at least
+//! four bytes. Transport messages produced by [`CipherState`] keep the 65535
+//! byte limit.
+//!
+//! Each `e` token generates a McEliece keypair, which takes far longer than
+//! the rest of the handshake.
+
+use std::fmt;
+
+use hkdf::Hkdf;
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::aead::{self, Aead, Cipher};
+use crate::error::{Error, Result};
+use crate::kem::{self, Ciphertext, PublicKey, SecretKey, SharedSecret};
+use crate::params::ParameterSet;
+
+/// Largest Noise transport message, ciphertext and tag included.
+pub const MAX_MESSAGE_LEN: usize = 65535;
+
+const HASH_LEN: usize = 32;
+
+/// A handshake pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    /// No static keys: unauthenticated, like Noise NN.
+    NN,
+    /// Both static keys are transmitted during the handshake, like Noise XX.
+    XX,
+    /// The initiator knows the responder's static key in advance and sends
+    /// its own in the first message, like Noise IK.
+    IK,
+}
+
+#[derive(Clone, Copy)]
+enum Token {
+    E,
+    Ekem,
+    S,
+    Skem,
+}
+
+impl Pattern {
+    /// The pattern's name as used in the protocol name, e.g. `pqXX`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Pattern::NN => "pqNN",
+            Pattern::XX => "pqXX",
+            Pattern::IK => "pqIK",
+        }
+    }
+
+    fn messages(self) -> &'static [&'static [Token]] {
+        use Token::*;
+        match self {
+            Pattern::NN => &[&[E], &[Ekem]],
+            Pattern::XX => &[&[E], &[Ekem, S], &[Skem, S], &[Skem]],
+            Pattern::IK => &[&[Skem, E, S], &[Ekem, Skem]],
+        }
+    }
+
+    fn uses_static_keys(self) -> bool {
+        self != Pattern::NN
+    }
+
+    /// Whether the responder's static key is a pre-message.
+    fn responder_static_known(self) -> bool {
+        self == Pattern::IK
+    }
+}
+
+/// Which side of the handshake a party plays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// The party that writes the first message.
+    Initiator,
+    /// The party that reads the first message.
+    Responder,
+}
+
+/// A key and nonce for ChaCha20-Poly1305, as in the Noise specification.
+pub struct CipherState {
+    cipher: Option<Cipher>,
+    nonce: u64,
+}
+
+impl CipherState {
+    fn empty() -> CipherState {
+        CipherState {
+            cipher: None,
+            nonce: 0,
+        }
+    }
+
+    fn with_key(key: &[u8; aead::KEY_LEN]) -> CipherState {
+        CipherState {
+            cipher: Some(Cipher::new(Aead::ChaCha20Poly1305, key)),
+            nonce: 0,
+        }
+    }
+
+    fn has_key(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    fn overhead(&self) -> usize {
+        if self.has_key() {
+            aead::TAG_LEN
+        } else {
+            0
+        }
+    }
+
+    /// The AEAD nonce for the current counter: 32 zero bits, then the
+    /// counter in little-endian order.
+    fn aead_nonce(&self) -> Result<[u8; aead::NONCE_LEN]> {
+        // The Noise specification reserves the maximum counter value.
+        if self.nonce == u64::MAX {
+            return Err(Error::Protocol("cipher state nonce exhausted"));
+        }
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        nonce[4..].copy_from_slice(&self.nonce.to_le_bytes());
+        Ok(nonce)
+    }
+
+    fn seal(&mut self, ad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut buffer = plaintext.to_vec();
+        if let Some(cipher) = &self.cipher {
+            cipher.seal(&self.aead_nonce()?, ad, &mut buffer)?;
+            self.nonce += 1;
+        }
+        Ok(buffer)
+    }
+
+    fn open(&mut self, ad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let mut buffer = ciphertext.to_vec();
+        if let Some(cipher) = &self.cipher {
+            // A failed decryption leaves the counter unchanged.
+            cipher.open(&self.aead_nonce()?, ad, &mut buffer)?;
+            self.nonce += 1;
+        }
+        Ok(buffer)
+    }
+
+    /// Encrypts a transport message, appending a 16-byte tag.
+    ///
+    /// Fails if the result would exceed [`MAX_MESSAGE_LEN`] or the nonce is
+    /// exhausted.
+    pub fn encrypt_with_ad(&mut self, ad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        if plaintext.len() + self.overhead() > MAX_MESSAGE_LEN {
+            return Err(Error::Format("Noise transport message too long"));
+        }
+        self.seal(ad, plaintext)
+    }
+
+    /// Decrypts a transport message.
+    ///
+    /// Fails with [`Error::Decryption`] if the message was not produced by
+    /// the peer's matching cipher state or was altered.
+    pub fn decrypt_with_ad(&mut self, ad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        if ciphertext.len() > MAX_MESSAGE_LEN {
+            return Err(Error::Format("Noise transport message too long"));
+        }
+        self.open(ad, ciphertext)
+    }
+
+    /// Number of messages processed so far.
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+}
+
+impl fmt::Debug for CipherState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CipherState")
+            .field("has_key", &self.has_key())
+            .field("nonce", &self.nonce)
+            .finish()
+    }
+}
+
+struct SymmetricState {
+    chaining_key: Zeroizing<[u8; HASH_LEN]>,
+    hash: [u8; HASH_LEN],
+    cipher: CipherState,
+}
+
+/// The Noise `HKDF` function with two outputs.
+fn hkdf2(
+    chaining_key: &[u8; HASH_LEN],
+    input: &[u8],
+) -> (Zeroizing<[u8; HASH_LEN]>, Zeroizing<[u8; HASH_LEN]>) {
+    let mut output = Zeroizing::new([0u8; 2 * HASH_LEN]);
+    Hkdf::<Sha256>::new(Some(chaining_key), input)
+        .expand(&[], output.as_mut())
+        .expect("64 bytes is a valid HKDF-SHA256 output length");
+    let (first, second) = output.split_at(HASH_LEN);
+    (
+        Zeroizing::new(first.try_into().expect("split at HASH_LEN")),
+        Zeroizing::new(second.try_into().expect("split at HASH_LEN")),
+    )
+}
+
+impl SymmetricState {
+    fn new(protocol_name: &[u8]) -> SymmetricState {
+        let mut hash = [0u8; HASH_LEN];
+        if protocol_name.len() <= HASH_LEN {
+            hash[..protocol_name.len()].copy_from_slice(protocol_name);
+        } else {
+            hash = Sha256::digest(protocol_name).into();
+        }
+        SymmetricState {
+            chaining_key: Zeroizing::new(hash),
+            hash,
+            cipher: CipherState::empty(),
+        }
+    }
+
+    fn mix_key(&mut self, input: &SharedSecret) {
+        let (chaining_key, key) = hkdf2(&self.chaining_key, input.as_bytes());
+        self.chaining_key = chaining_key;
+        self.cipher = CipherState::with_key(&key);
+    }
+
+    fn mix_hash(&mut self, data: &[u8]) {
+        self.hash = Sha256::new()
+            .chain_update(self.hash)
+            .chain_update(data)
+            .finalize()
+            .into();
+    }
+
+    fn encrypt_and_hash(&mut self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let ciphertext = self.cipher.seal(&self.hash, plaintext)?;
+        self.mix_hash(&ciphertext);
+        Ok(ciphertext)
+    }
+
+    fn decrypt_and_hash(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let plaintext = self.cipher.open(&self.hash, ciphertext)?;
+        self.mix_hash(ciphertext);
+        Ok(plaintext)
+    }
+
+    fn split(&self) -> (CipherState, CipherState) {
+        let (first, second) = hkdf2(&self.chaining_key, &[]);
+        (
+            CipherState::with_key(&first),
+            CipherState::with_key(&second),
+        )
+    }
+}
+
+/// Splits `len` bytes off the front of `rest`.
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if rest.len() < len {
+        return Err(Error::Format("Noise handshake message too short"));
+    }
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+    Ok(head)
+}
+
+/// One party's state during a handshake.
+///
+/// Messages alternate between the parties, starting with the initiator.
+/// Once [`is_finished`](Self::is_finished) returns true, [`split`](Self::split)
+/// yields the transport cipher states. After any error the handshake must be
+/// abandoned.
+pub struct HandshakeState {
+    pattern: Pattern,
+    role: Role,
+    symmetric: SymmetricState,
+    local_static: Option<(PublicKey, SecretKey)>,
+    local_ephemeral: Option<(PublicKey, SecretKey)>,
+    remote_static: Option<PublicKey>,
+    remote_ephemeral: Option<PublicKey>,
+    message: usize,
+}
+
+impl HandshakeState {
+    /// Starts a handshake.
+    ///
+    /// `local_static` is required by `pqXX` and `pqIK` and must be absent for
+    /// `pqNN`. `remote_static` is the responder's public key, required for a
+    /// `pqIK` initiator and rejected otherwise. Both parties must pass the
+    /// same `prologue`.
+    pub fn new(
+        pattern: Pattern,
+        role: Role,
+        prologue: &[u8],
+        local_static: Option<(PublicKey, SecretKey)>,
+        remote_static: Option<PublicKey>,
+    ) -> Result<HandshakeState> {
+        if pattern.uses_static_keys() != local_static.is_some() {
+            return Err(Error::Protocol(if pattern.uses_static_keys() {
+                "the handshake pattern requires a local static key"
+            } else {
+                "the handshake pattern does not use static keys"
+            }));
+        }
+        let remote_known = pattern.responder_static_known() && role == Role::Initiator;
+        if remote_known != remote_static.is_some() {
+            return Err(Error::Protocol(if remote_known {
+                "the handshake pattern requires the responder's static key"
+            } else {
+                "the handshake pattern does not take a remote static key"
+            }));
+        }
+
+        let protocol_name = format!(
+            "Noise_{}_{}_ChaChaPoly_SHA256",
+            pattern.name(),
+            ParameterSet::compiled()
+        );
+        let mut symmetric = SymmetricState::new(protocol_name.as_bytes());
+        symmetric.mix_hash(prologue);
+        if pattern.responder_static_known() {
+            let responder_static = match role {
+                Role::Initiator => remote_static.as_ref(),
+                Role::Responder => local_static.as_ref().map(|(public_key, _)| public_key),
+            };
+            symmetric.mix_hash(responder_static.expect("checked above").as_bytes());
+        }
+
+        Ok(HandshakeState {
+            pattern,
+            role,
+            symmetric,
+            local_static,
+            local_ephemeral: None,
+            remote_static,
+            remote_ephemeral: None,
+            message: 0,
+        })
+    }
+
+    /// Whether the next message is this party's to write.
+    pub fn is_my_turn(&self) -> bool {
+        !self.is_finished() && self.message.is_multiple_of(2) == (self.role == Role::Initiator)
+    }
+
+    /// Whether all handshake messages have been exchanged.
+    pub fn is_finished(&self) -> bool {
+        self.message == self.pattern.messages().len()
+    }
+
+    /// The peer's static public key, once known.
+    pub fn remote_static(&self) -> Option<&PublicKey> {
+        self.remote_static.as_ref()
+    }
+
+    /// The handshake hash, identical on both sides once the handshake is
+    /// finished. Suitable for channel binding.
+    pub fn handshake_hash(&self) -> &[u8; 32] {
+        &self.symmetric.hash
+    }
+
+    fn tokens(&self, writing: bool) -> Result<&'static [Token]> {
+        if self.is_finished() {
+            return Err(Error::Protocol("the handshake is already finished"));
+        }
+        if self.is_my_turn() != writing {
+            return Err(Error::Protocol(if writing {
+                "it is the peer's turn to send a handshake message"
+            } else {
+                "it is this party's turn to send a handshake message"
+            }));
+        }
+        Ok(self.pattern.messages()[self.message])
+    }
+
+    /// Writes the next handshake message, carrying `payload`.
+    ///
+    /// The payload is encrypted if a key has been established by this point
+    /// of the pattern, which is not the case for the first message of `pqNN`
+    /// and `pqXX`.
+    pub fn write_message<R: RngCore + CryptoRng>(
+        &mut self,
+        payload: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        for &token in self.tokens(true)? {
+            match token {
+                Token::E => {
+                    let (public_key, secret_key) = kem::generate(rng)?;
+                    self.symmetric.mix_hash(public_key.as_bytes());
+                    out.extend_from_slice(public_key.as_bytes());
+                    self.local_ephemeral = Some((public_key, secret_key));
+                }
+                Token::Ekem => {
+                    let remote = self.remote_ephemeral.as_ref().expect("sent earlier");
+                    let (ciphertext, shared_secret) = kem::encapsulate(remote, rng)?;
+                    self.symmetric.mix_hash(ciphertext.as_bytes());
+                    self.symmetric.mix_key(&shared_secret);
+                    out.extend_from_slice(ciphertext.as_bytes());
+                }
+                Token::S => {
+                    let (public_key, _) = self.local_static.as_ref().expect("checked in new");
+                    let sealed = self.symmetric.encrypt_and_hash(public_key.as_bytes())?;
+                    out.extend_from_slice(&sealed);
+                }
+                Token::Skem => {
+                    let remote = self.remote_static.as_ref().expect("sent earlier");
+                    let (ciphertext, shared_secret) = kem::encapsulate(remote, rng)?;
+                    let sealed = self.symmetric.encrypt_and_hash(ciphertext.as_bytes())?;
+                    self.symmetric.mix_key(&shared_secret);
+                    out.extend_from_slice(&sealed);
+                }
+            }
+        }
+        out.extend_from_slice(&self.symmetric.encrypt_and_hash(payload)?);
+        self.message += 1;
+        Ok(out)
+    }
+
+    /// Reads the peer's next handshake message and returns its payload.
+    pub fn read_message(&mut self, message: &[u8]) -> Result<Vec<u8>> {
+        let mut rest = message;
+        for &token in self.tokens(false)? {
+            match token {
+                Token::E => {
+                    let public_key = PublicKey::from_bytes(take(&mut rest, PublicKey::LEN)?)?;
+                    self.symmetric.mix_hash(public_key.as_bytes());
+                    self.remote_ephemeral = Some(public_key);
+                }
+                Token::Ekem => {
+                    let ciphertext = Ciphertext::from_bytes(take(&mut rest, Ciphertext::LEN)?)?;
+                    self.symmetric.mix_hash(ciphertext.as_bytes());
+                    let (_, secret_key) = self.local_ephemeral.as_ref().expect("sent earlier");
+                    let shared_secret = kem::decapsulate(&ciphertext, secret_key)?;
+                    self.symmetric.mix_key(&shared_secret);
+                }
+                Token::S => {
+                    let len = PublicKey::LEN + self.symmetric.cipher.overhead();
+                    let opened = self.symmetric.decrypt_and_hash(take(&mut rest, len)?)?;
+                    self.remote_static = Some(PublicKey::from_bytes(&opened)?);
+                }
+                Token::Skem => {
+                    let len = Ciphertext::LEN + self.symmetric.cipher.overhead();
+                    let opened = self.symmetric.decrypt_and_hash(take(&mut rest, len)?)?;
+                    let ciphertext = Ciphertext::from_bytes(&opened)?;
+                    let (_, secret_key) = self.local_static.as_ref().expect("checked in new");
+                    let shared_secret = kem::decapsulate(&ciphertext, secret_key)?;
+                    self.symmetric.mix_key(&shared_secret);
+                }
+            }
+        }
+        let payload = self.symmetric.decrypt_and_hash(rest)?;
+        self.message += 1;
+        Ok(payload)
+    }
+
+    /// Ends the handshake, returning this party's `(send, receive)` cipher
+    /// states.
+    ///
+    /// The Noise specification's `Split()` returns the initiator's sending
+    /// state first; this method orders the pair by role instead.
+    pub fn split(self) -> Result<(CipherState, CipherState)> {
+        if !self.is_finished() {
+            return Err(Error::Protocol("the handshake is not finished"));
+        }
+        let (initiator_to_responder, responder_to_initiator) = self.symmetric.split();
+        Ok(match self.role {
+            Role::Initiator => (initiator_to_responder, responder_to_initiator),
+            Role::Responder => (responder_to_initiator, initiator_to_responder),
+        })
+    }
+}
+
+impl fmt::Debug for HandshakeState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HandshakeState")
+            .field("pattern", &self.pattern)
+            .field("role", &self.role)
+            .field("message", &self.message)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,177 @@
+//! Classic McEliece parameter sets.
+//!
+//! `classic-mceliece-rust` fixes its parameter set at compile time through a
+//! Cargo feature, so a given build of this crate can run exactly one of the
+//! sets below (see [`ParameterSet::compiled`]). The remaining sets are still
+//! described here so that sizes can be reported and encoded artifacts can be
+//! recognised; asking the KEM to use one of them fails with
+//! [`Error::UnsupportedParameterSet`].
+//!
+//! Bindings that link every set at once, such as PQClean's through `pqcrypto`
+//! or liboqs, draw randomness from a process-wide source rather than a
+//! caller-supplied RNG. Switching to one would lose seeded key generation and
+//! the NIST DRBG behind the known-answer tests, so sets are chosen per build
+//! instead; build once per set to use several.
+
+use std::fmt;
+use std::str::FromStr;
+
+use classic_mceliece_rust::CRYPTO_PRIMITIVE;
+
+use crate::error::{Error, Result};
+
+/// One of the ten Classic McEliece parameter sets from the NIST submission.
+///
+/// The `f` variants use the semi-systematic key generation of the
+/// specification; their key and ciphertext sizes match the plain variants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ParameterSet {
+    McEliece348864,
+    McEliece348864f,
+    McEliece460896,
+    McEliece460896f,
+    McEliece6688128,
+    McEliece6688128f,
+    McEliece6960119,
+    McEliece6960119f,
+    McEliece8192128,
+    McEliece8192128f,
+}
+
+impl ParameterSet {
+    /// Every parameter set, smallest keys first.
+    pub const ALL: [ParameterSet; 10] = [
+        ParameterSet::McEliece348864,
+        ParameterSet::McEliece348864f,
+        ParameterSet::McEliece460896,
+        ParameterSet::McEliece460896f,
+        ParameterSet::McEliece6688128,
+        ParameterSet::McEliece6688128f,
+        ParameterSet::McEliece6960119,
+        ParameterSet::McEliece6960119f,
+        ParameterSet::McEliece8192128,
+        ParameterSet::McEliece8192128f,
+    ];
+
+    /// The parameter set `classic-mceliece-rust` was built with.
+    pub fn compiled() -> ParameterSet {
+        CRYPTO_PRIMITIVE
+            .parse()
+            .expect("backend reports a known parameter set")
+    }
+
+    /// Whether this build can generate keys and run the KEM for the set.
+    pub fn is_available(self) -> bool {
+        self == ParameterSet::compiled()
+    }
+
+    /// Returns `Ok(())` if the set is available in this build.
+    pub fn ensure_available(self) -> Result<()> {
+        if self.is_available() {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedParameterSet(self))
+        }
+    }
+
+    /// The specification name, e.g. `"mceliece348864f"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            ParameterSet::McEliece348864 => "mceliece348864",
+            ParameterSet::McEliece348864f => "mceliece348864f",
+            ParameterSet::McEliece460896 => "mceliece460896",
+            ParameterSet::McEliece460896f => "mceliece460896f",
+            ParameterSet::McEliece6688128 => "mceliece6688128",
+            ParameterSet::McEliece6688128f => "mceliece6688128f",
+            ParameterSet::McEliece6960119 => "mceliece6960119",
+            ParameterSet::McEliece6960119f => "mceliece6960119f",
+            ParameterSet::McEliece8192128 => "mceliece8192128",
+            ParameterSet::McEliece8192128f => "mceliece8192128f",
+        }
+    }
+
+    /// The one-byte identifier used in encoded keys and ciphertexts.
+    pub const fn id(self) -> u8 {
+        match self {
+            ParameterSet::McEliece348864 => 1,
+            ParameterSet::McEliece348864f => 2,
+            ParameterSet::McEliece460896 => 3,
+            ParameterSet::McEliece460896f => 4,
+            ParameterSet::McEliece6688128 => 5,
+            ParameterSet::McEliece6688128f => 6,
+            ParameterSet::McEliece6960119 => 7,
+            ParameterSet::McEliece6960119f => 8,
+            ParameterSet::McEliece8192128 => 9,
+            ParameterSet::McEliece8192128f => 10,
+        }
+    }
+
+    /// Looks up a parameter set by its [`id`](ParameterSet::id).
+    pub fn from_id(id: u8) -> Option<ParameterSet> {
+        ParameterSet::ALL.into_iter().find(|set| set.id() == id)
+    }
+
+    /// NIST security category claimed by the submission.
+    pub const fn security_level(self) -> u8 {
+        match self {
+            ParameterSet::McEliece348864 | ParameterSet::McEliece348864f => 1,
+            ParameterSet::McEliece460896 | ParameterSet::McEliece460896f => 3,
+            _ => 5,
+        }
+    }
+
+    /// Public key length in bytes.
+    pub const fn public_key_len(self) -> usize {
+        match self {
+            ParameterSet::McEliece348864 | ParameterSet::McEliece348864f => 261_120,
+            ParameterSet::McEliece460896 | ParameterSet::McEliece460896f => 524_160,
+            ParameterSet::McEliece6688128 | ParameterSet::McEliece6688128f => 1_044_992,
+            ParameterSet::McEliece6960119 | ParameterSet::McEliece6960119f => 1_047_319,
+            ParameterSet::McEliece8192128 | ParameterSet::McEliece8192128f => 1_357_824,
+        }
+    }
+
+    /// Secret key length in bytes.
+    pub const fn secret_key_len(self) -> usize {
+        match self {
+            ParameterSet::McEliece348864 | ParameterSet::McEliece348864f => 6_492,
+            ParameterSet::McEliece460896 | ParameterSet::McEliece460896f => 13_608,
+            ParameterSet::McEliece6688128 | ParameterSet::McEliece6688128f => 13_932,
+            ParameterSet::McEliece6960119 | ParameterSet::McEliece6960119f => 13_948,
+            ParameterSet::McEliece8192128 | ParameterSet::McEliece8192128f => 14_120,
+        }
+    }
+
+    /// Ciphertext length in bytes.
+    pub const fn ciphertext_len(self) -> usize {
+        match self {
+            ParameterSet::McEliece348864 | ParameterSet::McEliece348864f => 96,
+            ParameterSet::McEliece460896 | ParameterSet::McEliece460896f => 156,
+            ParameterSet::McEliece6688128 | ParameterSet::McEliece6688128f => 208,
+            ParameterSet::McEliece6960119 | ParameterSet::McEliece6960119f => 194,
+            ParameterSet::McEliece8192128 | ParameterSet::McEliece8192128f => 208,
+        }
+    }
+
+    /// Shared secret length in bytes; the same for every set.
+    pub const fn shared_secret_len(self) -> usize {
+        32
+    }
+}
+
+impl fmt::Display for ParameterSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ParameterSet {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        ParameterSet::ALL
+            .into_iter()
+            .find(|set| set.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::UnknownParameterSet(s.to_owned()))
+    }
+}
@@ -0,0 +1,187 @@
+//! Passphrase-protected storage for secret keys.
+//!
+//! The secret key's versioned encoding (see [`crate::encoding`]) is encrypted
+//! with an AEAD under a key derived from a passphrase with Argon2id. The
+//! Argon2 cost parameters live in the header so they can be raised over time
+//! without breaking existing files:
+//!
+//! ```text
+//! "MCEP" | version (1) | aead id (1) | kdf id = 1 (Argon2id) | reserved = 0 (1)
+//!        | memory KiB (u32 BE) | iterations (u32 BE) | parallelism (u32 BE)
+//!        | salt (16) | nonce (12) | AEAD(encoded secret key)
+//! ```
+//!
+//! Everything before the AEAD ciphertext is authenticated as associated data.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::aead::{Aead, Cipher, KEY_LEN, NONCE_LEN, TAG_LEN};
+use crate::error::{Error, Result};
+use crate::{hybrid, kem};
+
+const MAGIC: &[u8; 4] = b"MCEP";
+const VERSION: u8 = 1;
+const KDF_ARGON2ID: u8 = 1;
+const SALT_LEN: usize = 16;
+const HEADER_LEN: usize = 4 + 1 + 1 + 1 + 1 + 3 * 4 + SALT_LEN + NONCE_LEN;
+
+/// Largest memory cost [`unprotect`] accepts (1 GiB), so a crafted header
+/// cannot make us allocate without bound. This is 16 times the default.
+pub const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+/// Largest iteration count [`unprotect`] accepts. With the memory cap, this
+/// bounds the work a crafted header can demand to 16 GiB of memory passes.
+pub const MAX_ITERATIONS: u32 = 16;
+/// Largest degree of parallelism [`unprotect`] accepts.
+pub const MAX_PARALLELISM: u32 = 64;
+
+/// Argon2id cost parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory in KiB.
+    pub memory_kib: u32,
+    /// Number of passes over memory.
+    pub iterations: u32,
+    /// Degree of parallelism (lanes).
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    /// 64 MiB, three passes, one lane.
+    fn default() -> Self {
+        KdfParams {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    fn argon2(self) -> Result<Argon2<'static>> {
+        let params = Params::new(
+            self.memory_kib,
+            self.iterations,
+            self.parallelism,
+            Some(KEY_LEN),
+        )
+        .map_err(|_| Error::Format("Argon2 cost parameters out of range"))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    fn derive_key(self, passphrase: &[u8], salt: &[u8]) -> Result<Zeroizing<[u8; KEY_LEN]>> {
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        self.argon2()?
+            .hash_password_into(passphrase, salt, &mut *key)
+            .map_err(|_| Error::Format("invalid Argon2 input"))?;
+        Ok(key)
+    }
+}
+
+/// Returns whether `bytes` looks like a passphrase-protected container.
+pub fn is_protected(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Encrypts an encoded secret key under `passphrase`.
+///
+/// `params` above the `MAX_*` limits are not refused here, but [`unprotect`]
+/// will not open the result.
+pub fn protect<R: RngCore + CryptoRng>(
+    encoded: &[u8],
+    passphrase: &[u8],
+    params: KdfParams,
+    rng: &mut R,
+) -> Result<Vec<u8>> {
+    let aead = Aead::default();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut nonce);
+    let key = params.derive_key(passphrase, &salt)?;
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&[VERSION, aead.id(), KDF_ARGON2ID, 0]);
+    header.extend_from_slice(&params.memory_kib.to_be_bytes());
+    header.extend_from_slice(&params.iterations.to_be_bytes());
+    header.extend_from_slice(&params.parallelism.to_be_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    // Room for the tag, so sealing in place never reallocates and leaves a
+    // plaintext copy behind.
+    let mut body = Zeroizing::new(Vec::with_capacity(encoded.len() + TAG_LEN));
+    body.extend_from_slice(encoded);
+    Cipher::new(aead, &key).seal(&nonce, &header, &mut body)?;
+    header.extend_from_slice(&body);
+    Ok(header)
+}
+
+/// Decrypts a container produced by [`protect`], returning the encoded
+/// secret key. Fails with [`Error::Decryption`] on a wrong passphrase.
+pub fn unprotect(container: &[u8], passphrase: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+    if container.len() < HEADER_LEN {
+        return Err(Error::Format("truncated protected key"));
+    }
+    let (header, body) = container.split_at(HEADER_LEN);
+    if !is_protected(header) {
+        return Err(Error::Format("not a protected key"));
+    }
+    if header[4] != VERSION {
+        return Err(Error::Format("unsupported protected key version"));
+    }
+    let aead = Aead::from_id(header[5])?;
+    if header[6] != KDF_ARGON2ID {
+        return Err(Error::Format("unknown key derivation function"));
+    }
+    if header[7] != 0 {
+        return Err(Error::Format("reserved header byte is not zero"));
+    }
+    let word = |at: usize| u32::from_be_bytes(header[at..at + 4].try_into().expect("4 bytes"));
+    let params = KdfParams {
+        memory_kib: word(8),
+        iterations: word(12),
+        parallelism: word(16),
+    };
+    if params.memory_kib > MAX_MEMORY_KIB
+        || params.iterations > MAX_ITERATIONS
+        || params.parallelism > MAX_PARALLELISM
+    {
+        return Err(Error::Format("Argon2 cost parameters out of range"));
+    }
+    let salt = &header[20..20 + SALT_LEN];
+    let nonce: &[u8; NONCE_LEN] = header[20 + SALT_LEN..]
+        .try_into()
+        .expect("header ends with the nonce");
+
+    let key = params.derive_key(passphrase, salt)?;
+    let mut encoded = Zeroizing::new(body.to_vec());
+    Cipher::new(aead, &key).open(nonce, header, &mut encoded)?;
+    Ok(encoded)
+}
+
+macro_rules! impl_protected {
+    ($ty:ty) => {
+        impl $ty {
+            /// Encrypts the key under `passphrase`; see [`crate::passphrase`].
+            pub fn to_protected<R: RngCore + CryptoRng>(
+                &self,
+                passphrase: &[u8],
+                params: KdfParams,
+                rng: &mut R,
+            ) -> Result<Vec<u8>> {
+                protect(&self.to_encoded(), passphrase, params, rng)
+            }
+
+            /// Decrypts a key stored with `to_protected`.
+            pub fn from_protected(container: &[u8], passphrase: &[u8]) -> Result<Self> {
+                Self::from_encoded(&unprotect(container, passphrase)?)
+            }
+        }
+    };
+}
+
+impl_protected!(kem::SecretKey);
+impl_protected!(hybrid::SecretKey);
@@ -0,0 +1,170 @@
+//! A pool of keypairs generated ahead of demand on background threads.
+//!
+//! Key generation costs hundreds of times more than encapsulation, so a server
+//! that needs a fresh keypair per session should not generate it on the
+//! request path. [`KeyPool`] keeps up to a target number of keypairs ready and
+//! refills as they are taken.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use crate::kem::{self, PublicKey, SecretKey};
+
+/// Keypairs generated in the background, up to a target size.
+///
+/// Dropping the pool stops its workers and discards the pooled keys. Workers
+/// finish the keypair they are generating first, so the drop can block for
+/// up to one key generation.
+pub struct KeyPool {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+struct Shared {
+    target: usize,
+    state: Mutex<State>,
+    /// Signalled when a keypair is added.
+    ready: Condvar,
+    /// Signalled when a keypair is taken or the pool shuts down.
+    wanted: Condvar,
+}
+
+struct State {
+    keys: VecDeque<(PublicKey, SecretKey)>,
+    /// Keypairs currently being generated.
+    pending: usize,
+    shutdown: bool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // Keys are only pushed and popped under the lock, so the state is
+        // consistent even if a thread panicked while holding it.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl KeyPool {
+    /// Starts `workers` threads that keep `target` keypairs ready for the
+    /// compiled parameter set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` or `workers` is zero, or if a thread cannot be
+    /// spawned.
+    pub fn new(target: usize, workers: usize) -> KeyPool {
+        assert!(target > 0, "key pool target size must be positive");
+        assert!(workers > 0, "key pool needs at least one worker");
+        let shared = Arc::new(Shared {
+            target,
+            state: Mutex::new(State {
+                keys: VecDeque::with_capacity(target),
+                pending: 0,
+                shutdown: false,
+            }),
+            ready: Condvar::new(),
+            wanted: Condvar::new(),
+        });
+        let workers = (0..workers)
+            .map(|i| {
+                let shared = Arc::clone(&shared);
+                thread::Builder::new()
+                    .name(format!("mce-keypool-{i}"))
+                    .spawn(move || work(&shared))
+                    .expect("failed to spawn key pool worker")
+            })
+            .collect();
+        KeyPool { shared, workers }
+    }
+
+    /// Takes a keypair, waiting for one to be generated if the pool is empty.
+    pub fn take(&self) -> (PublicKey, SecretKey) {
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(keypair) = state.keys.pop_front() {
+                self.shared.wanted.notify_one();
+                return keypair;
+            }
+            state = self
+                .shared
+                .ready
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    /// Takes a keypair if one is ready, without waiting.
+    pub fn try_take(&self) -> Option<(PublicKey, SecretKey)> {
+        let keypair = self.shared.lock().keys.pop_front();
+        if keypair.is_some() {
+            self.shared.wanted.notify_one();
+        }
+        keypair
+    }
+
+    /// Number of keypairs ready to be taken.
+    pub fn len(&self) -> usize {
+        self.shared.lock().keys.len()
+    }
+
+    /// Whether no keypair is ready.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of keypairs the pool tries to keep ready.
+    pub fn target(&self) -> usize {
+        self.shared.target
+    }
+}
+
+impl fmt::Debug for KeyPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPool")
+            .field("target", &self.shared.target)
+            .field("ready", &self.len())
+            .field("workers", &self.workers.len())
+            .finish()
+    }
+}
+
+impl Drop for KeyPool {
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.wanted.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn work(shared: &Shared) {
+    let mut rng = rand::thread_rng();
+    let mut state = shared.lock();
+    loop {
+        if state.shutdown {
+            return;
+        }
+        if state.keys.len() + state.pending >= shared.target {
+            state = shared
+                .wanted
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            continue;
+        }
+        state.pending += 1;
+        drop(state);
+
+        let keypair =
+            kem::generate(&mut rng).expect("the compiled parameter set is always available");
+
+        state = shared.lock();
+        state.pending -= 1;
+        state.keys.push_back(keypair);
+        shared.ready.notify_one();
+    }
+}
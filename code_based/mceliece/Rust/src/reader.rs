@@ -0,0 +1,221 @@
+//! Incremental parsing of public keys as they arrive.
+//!
+//! A public key is hundreds of kilobytes, which network code usually receives
+//! in many small reads. [`PublicKeyReader`] accepts it piecewise, checks the
+//! encoding header as soon as its 12 bytes are in, and writes the key bytes
+//! straight into the buffer the finished [`PublicKey`] takes over, so no
+//! second key-sized buffer is ever allocated.
+
+use std::fmt;
+use std::io::{self, Read};
+
+use sha3::{Digest, Sha3_256};
+
+use crate::encoding::{Header, Kind, CHECKSUM_LEN, HEADER_LEN};
+use crate::error::{Error, Result};
+use crate::kem::PublicKey;
+
+/// Builds a [`PublicKey`] from chunks of its encoding.
+///
+/// Feed bytes with [`update`](Self::update), or let
+/// [`read_from`](Self::read_from) pull them from a reader, then call
+/// [`finish`](Self::finish). The reader never consumes more bytes than the
+/// key needs, so data following the key is left to the caller. After an
+/// error the reader must be discarded.
+pub struct PublicKeyReader {
+    /// Whether the input has the versioned encoding's header and checksum.
+    encoded: bool,
+    header: [u8; HEADER_LEN],
+    /// Allocated once the header has been validated.
+    body: Option<Box<[u8; PublicKey::LEN]>>,
+    checksum: [u8; CHECKSUM_LEN],
+    hasher: Sha3_256,
+    /// Bytes consumed so far.
+    filled: usize,
+}
+
+impl PublicKeyReader {
+    /// A reader for the versioned encoding of [`PublicKey::to_encoded`].
+    pub fn new() -> PublicKeyReader {
+        PublicKeyReader::with_form(true)
+    }
+
+    /// A reader for the raw form of [`PublicKey::as_bytes`], exactly
+    /// [`PublicKey::LEN`] bytes with no header.
+    pub fn raw() -> PublicKeyReader {
+        PublicKeyReader::with_form(false)
+    }
+
+    fn with_form(encoded: bool) -> PublicKeyReader {
+        PublicKeyReader {
+            encoded,
+            header: [0; HEADER_LEN],
+            body: None,
+            checksum: [0; CHECKSUM_LEN],
+            hasher: Sha3_256::new(),
+            filled: 0,
+        }
+    }
+
+    /// Total number of bytes the key occupies in this form.
+    pub fn total_len(&self) -> usize {
+        if self.encoded {
+            HEADER_LEN + PublicKey::LEN + CHECKSUM_LEN
+        } else {
+            PublicKey::LEN
+        }
+    }
+
+    /// Number of bytes still needed.
+    pub fn remaining(&self) -> usize {
+        self.total_len() - self.filled
+    }
+
+    /// Whether all bytes of the key have been received.
+    pub fn is_complete(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Offset of the body within the input.
+    fn body_start(&self) -> usize {
+        if self.encoded {
+            HEADER_LEN
+        } else {
+            0
+        }
+    }
+
+    /// The part of the destination the next input bytes belong in.
+    fn next_buf(&mut self) -> &mut [u8] {
+        let body_start = self.body_start();
+        let body_end = body_start + PublicKey::LEN;
+        if self.filled < body_start {
+            &mut self.header[self.filled..]
+        } else if self.filled < body_end {
+            let body = self
+                .body
+                .get_or_insert_with(|| vec![0u8; PublicKey::LEN].try_into().expect("LEN bytes"));
+            &mut body[self.filled - body_start..]
+        } else {
+            &mut self.checksum[self.filled - body_end..]
+        }
+    }
+
+    /// Accounts for `n` bytes just written to [`next_buf`](Self::next_buf).
+    fn advance(&mut self, n: usize) -> Result<()> {
+        let body_start = self.body_start();
+        let start = self.filled;
+        self.filled += n;
+        if start < body_start && self.filled == body_start {
+            self.check_header()?;
+        } else if start >= body_start && start < body_start + PublicKey::LEN {
+            let body = self.body.as_ref().expect("allocated by next_buf");
+            self.hasher
+                .update(&body[start - body_start..self.filled - body_start]);
+        }
+        Ok(())
+    }
+
+    fn check_header(&mut self) -> Result<()> {
+        let header = Header::parse(&self.header)?;
+        if header.kind != Kind::PublicKey {
+            return Err(Error::WrongKind {
+                expected: Kind::PublicKey,
+                found: header.kind,
+            });
+        }
+        header.params.ensure_available()?;
+        self.hasher.update(self.header);
+        Ok(())
+    }
+
+    /// Consumes bytes from the front of `chunk`, returning how many were
+    /// used. Fewer than `chunk.len()` are used only once the key is
+    /// complete.
+    ///
+    /// Fails as soon as the header is known to be invalid or to describe
+    /// something other than a public key of the compiled parameter set.
+    pub fn update(&mut self, mut chunk: &[u8]) -> Result<usize> {
+        let mut used = 0;
+        while !chunk.is_empty() && !self.is_complete() {
+            let buf = self.next_buf();
+            let n = buf.len().min(chunk.len());
+            buf[..n].copy_from_slice(&chunk[..n]);
+            self.advance(n)?;
+            chunk = &chunk[n..];
+            used += n;
+        }
+        Ok(used)
+    }
+
+    /// Reads the rest of the key from `reader`, directly into the key's
+    /// buffer, and finishes it.
+    ///
+    /// Fails with [`Error::Format`] if the reader ends before the key does.
+    pub fn read_from<R: Read>(mut self, mut reader: R) -> Result<PublicKey> {
+        while !self.is_complete() {
+            let n = match reader.read(self.next_buf()) {
+                Ok(0) => return Err(Error::Format("input ended inside a public key")),
+                Ok(n) => n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            self.advance(n)?;
+        }
+        self.finish()
+    }
+
+    /// Like [`read_from`](Self::read_from), for a Tokio reader.
+    #[cfg(feature = "tokio")]
+    pub async fn read_from_async<R>(mut self, mut reader: R) -> Result<PublicKey>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        use tokio::io::AsyncReadExt;
+
+        while !self.is_complete() {
+            let n = reader.read(self.next_buf()).await?;
+            if n == 0 {
+                return Err(Error::Format("input ended inside a public key"));
+            }
+            self.advance(n)?;
+        }
+        self.finish()
+    }
+
+    /// Verifies the checksum and returns the key.
+    ///
+    /// Fails with [`Error::InvalidLength`] if bytes are still missing and
+    /// with [`Error::Checksum`] if the encoded key is corrupted.
+    pub fn finish(self) -> Result<PublicKey> {
+        if !self.is_complete() {
+            return Err(Error::InvalidLength {
+                what: "public key",
+                expected: self.total_len(),
+                actual: self.filled,
+            });
+        }
+        if self.encoded && self.hasher.finalize()[..CHECKSUM_LEN] != self.checksum {
+            return Err(Error::Checksum);
+        }
+        Ok(PublicKey::from_boxed(
+            self.body.expect("complete keys have a body"),
+        ))
+    }
+}
+
+impl Default for PublicKeyReader {
+    fn default() -> Self {
+        PublicKeyReader::new()
+    }
+}
+
+impl fmt::Debug for PublicKeyReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublicKeyReader")
+            .field("encoded", &self.encoded)
+            .field("filled", &self.filled)
+            .field("total_len", &self.total_len())
+            .finish()
+    }
+}
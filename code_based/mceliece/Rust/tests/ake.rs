@@ -0,0 +1,40 @@
+use mce::ake::{Initiator, Message1, Message2, Message3, Responder};
+use mce::Error;
+
+#[test]
+fn handshake_establishes_matching_sessions() {
+    let mut rng = rand::thread_rng();
+    let (alice_pk, alice_sk) = mce::generate(&mut rng).unwrap();
+    let (bob_pk, bob_sk) = mce::generate(&mut rng).unwrap();
+
+    let (initiator, m1) = Initiator::start(&alice_pk, &alice_sk, &bob_pk, &mut rng).unwrap();
+    let m1 = Message1::from_bytes(&m1.to_bytes()).unwrap();
+    let (responder, m2) = Responder::respond(&bob_pk, &bob_sk, &alice_pk, &m1, &mut rng).unwrap();
+    let m2 = Message2::from_bytes(&m2.to_bytes()).unwrap();
+    let (alice, m3) = initiator.finish(&m2).unwrap();
+    let m3 = Message3::from_bytes(&m3.to_bytes()).unwrap();
+    let bob = responder.finish(&m3).unwrap();
+
+    assert_eq!(alice.send_key(), bob.receive_key());
+    assert_eq!(alice.receive_key(), bob.send_key());
+    assert_ne!(alice.send_key(), alice.receive_key());
+    assert_eq!(alice.transcript_hash(), bob.transcript_hash());
+}
+
+#[test]
+fn handshake_rejects_unexpected_peer() {
+    let mut rng = rand::thread_rng();
+    let (alice_pk, alice_sk) = mce::generate(&mut rng).unwrap();
+    let (bob_pk, bob_sk) = mce::generate(&mut rng).unwrap();
+    let (mallory_pk, mallory_sk) = mce::generate(&mut rng).unwrap();
+
+    // Bob expects Mallory, so the key he encapsulates never reaches Alice.
+    let (initiator, m1) = Initiator::start(&alice_pk, &alice_sk, &bob_pk, &mut rng).unwrap();
+    let (_, m2) = Responder::respond(&bob_pk, &bob_sk, &mallory_pk, &m1, &mut rng).unwrap();
+    assert!(matches!(initiator.finish(&m2), Err(Error::Confirmation)));
+
+    // Mallory answers in Bob's place without Bob's secret key.
+    let (initiator, m1) = Initiator::start(&alice_pk, &alice_sk, &bob_pk, &mut rng).unwrap();
+    let (_, m2) = Responder::respond(&bob_pk, &mallory_sk, &alice_pk, &m1, &mut rng).unwrap();
+    assert!(matches!(initiator.finish(&m2), Err(Error::Confirmation)));
+}
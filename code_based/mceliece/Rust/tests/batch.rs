@@ -0,0 +1,37 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+#[test]
+fn results_follow_input_order() {
+    let mut rng = rand::thread_rng();
+    let (pk_a, sk_a) = mce::generate(&mut rng).unwrap();
+    let (pk_b, sk_b) = mce::generate(&mut rng).unwrap();
+    let public_keys = [pk_a.clone(), pk_b, pk_a];
+
+    let sent = mce::encapsulate_many(&public_keys, &mut rng).unwrap();
+    assert_eq!(sent.len(), 3);
+    for ((ciphertext, shared_secret), secret_key) in sent.iter().zip([&sk_a, &sk_b, &sk_a]) {
+        let received = mce::decapsulate(ciphertext, secret_key).unwrap();
+        assert_eq!(shared_secret.as_bytes(), received.as_bytes());
+    }
+    assert_ne!(sent[0].1.as_bytes(), sent[2].1.as_bytes());
+
+    let ciphertexts = [sent[2].0.clone(), sent[0].0.clone()];
+    let received = mce::decapsulate_many(&ciphertexts, &sk_a).unwrap();
+    assert_eq!(received[0].as_bytes(), sent[2].1.as_bytes());
+    assert_eq!(received[1].as_bytes(), sent[0].1.as_bytes());
+}
+
+#[test]
+fn output_depends_only_on_the_rng() {
+    let mut rng = rand::thread_rng();
+    let public_keys: Vec<_> = (0..2).map(|_| mce::generate(&mut rng).unwrap().0).collect();
+    let run = || {
+        mce::encapsulate_many(&public_keys, &mut ChaCha20Rng::from_seed([7; 32]))
+            .unwrap()
+            .into_iter()
+            .map(|(ciphertext, _)| ciphertext.as_bytes().to_vec())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(run(), run());
+}
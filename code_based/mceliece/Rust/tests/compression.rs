@@ -0,0 +1,24 @@
+//! Measures how well general-purpose compression shrinks public keys.
+//!
+//! Run with `--nocapture` to see the ratios. The matrix part of a Classic
+//! McEliece public key is indistinguishable from random bits, so compression
+//! only adds framing overhead; see the `encoding` module documentation.
+
+use mce::ParameterSet;
+
+#[test]
+fn public_keys_do_not_compress() {
+    let (public_key, _) = mce::generate(&mut rand::thread_rng()).unwrap();
+    let raw = public_key.as_bytes();
+    for level in [3, 19, 22] {
+        let compressed = zstd::encode_all(raw, level).unwrap();
+        let ratio = raw.len() as f64 / compressed.len() as f64;
+        println!(
+            "{} zstd -{level}: {} -> {} bytes, ratio {ratio:.5}",
+            ParameterSet::compiled(),
+            raw.len(),
+            compressed.len()
+        );
+        assert!(ratio < 1.01, "public keys unexpectedly compress");
+    }
+}
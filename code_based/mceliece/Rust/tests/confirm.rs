@@ -0,0 +1,27 @@
+use mce::confirm::{self, Role};
+use mce::Error;
+
+#[test]
+fn tags_bind_secret_role_and_transcript() {
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = mce::generate(&mut rng).unwrap();
+    let (ciphertext, sent) = mce::encapsulate(&public_key, &mut rng).unwrap();
+    let received = mce::decapsulate(&ciphertext, &secret_key).unwrap();
+    let (_, unrelated) = mce::encapsulate(&public_key, &mut rng).unwrap();
+    let transcript = [public_key.as_bytes(), ciphertext.as_bytes()].concat();
+
+    for role in [Role::Encapsulator, Role::Decapsulator] {
+        let tag = confirm::tag(&sent, role, &transcript);
+        confirm::verify(&received, role, &transcript, &tag).unwrap();
+    }
+
+    let tag = confirm::tag(&sent, Role::Encapsulator, &transcript);
+    for result in [
+        confirm::verify(&received, Role::Decapsulator, &transcript, &tag),
+        confirm::verify(&received, Role::Encapsulator, &transcript[1..], &tag),
+        confirm::verify(&unrelated, Role::Encapsulator, &transcript, &tag),
+        confirm::verify(&received, Role::Encapsulator, &transcript, &tag[..16]),
+    ] {
+        assert!(matches!(result, Err(Error::Confirmation)));
+    }
+}
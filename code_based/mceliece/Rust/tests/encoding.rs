@@ -0,0 +1,93 @@
+use mce::encoding::{Header, Kind, CHECKSUM_LEN, HEADER_LEN};
+use mce::{Ciphertext, Error, ParameterSet};
+
+fn encoded() -> Vec<u8> {
+    let body: Vec<u8> = (0..Ciphertext::LEN).map(|i| i as u8).collect();
+    Ciphertext::from_bytes(&body).unwrap().to_encoded()
+}
+
+#[test]
+fn round_trip() {
+    let bytes = encoded();
+    assert_eq!(bytes.len(), HEADER_LEN + Ciphertext::LEN + CHECKSUM_LEN);
+    let header = Header::parse(&bytes).unwrap();
+    assert_eq!(header.kind, Kind::Ciphertext);
+    assert_eq!(header.params, ParameterSet::compiled());
+    assert_eq!(header.encoded_len(), bytes.len());
+    assert_eq!(
+        Ciphertext::from_encoded(&bytes).unwrap().to_encoded(),
+        bytes
+    );
+}
+
+#[test]
+fn rejects_wrong_lengths() {
+    let bytes = encoded();
+    assert!(matches!(
+        Ciphertext::from_encoded(&bytes[..bytes.len() - 1]),
+        Err(Error::InvalidLength { .. })
+    ));
+    assert!(matches!(
+        Ciphertext::from_encoded(&bytes[..HEADER_LEN - 1]),
+        Err(Error::Format(_))
+    ));
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(
+        Ciphertext::from_encoded(&trailing),
+        Err(Error::InvalidLength { .. })
+    ));
+}
+
+#[test]
+fn rejects_wrong_header_fields() {
+    let bytes = encoded();
+
+    assert!(matches!(
+        mce::PublicKey::from_encoded(&bytes),
+        Err(Error::WrongKind {
+            expected: Kind::PublicKey,
+            found: Kind::Ciphertext,
+        })
+    ));
+
+    let mut unknown = bytes.clone();
+    unknown[6] = 0xee;
+    assert!(matches!(
+        Ciphertext::from_encoded(&unknown),
+        Err(Error::Format(_))
+    ));
+
+    // The `f` variant has the same sizes, so only availability fails.
+    let compiled = ParameterSet::compiled();
+    let other = ParameterSet::ALL
+        .into_iter()
+        .find(|set| *set != compiled && set.ciphertext_len() == compiled.ciphertext_len())
+        .unwrap();
+    let mut unavailable = bytes.clone();
+    unavailable[6] = other.id();
+    assert!(matches!(
+        Ciphertext::from_encoded(&unavailable),
+        Err(Error::UnsupportedParameterSet(set)) if set == other
+    ));
+
+    let mut reserved = bytes.clone();
+    reserved[7] = 1;
+    assert!(matches!(
+        Ciphertext::from_encoded(&reserved),
+        Err(Error::Format(_))
+    ));
+}
+
+#[test]
+fn rejects_checksum_mismatch() {
+    let bytes = encoded();
+    for at in [HEADER_LEN, bytes.len() - 1] {
+        let mut corrupted = bytes.clone();
+        corrupted[at] ^= 0x01;
+        assert!(
+            matches!(Ciphertext::from_encoded(&corrupted), Err(Error::Checksum)),
+            "byte {at}"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use mce::file::{self, DEFAULT_CHUNK_SIZE};
+use mce::{Aead, Error};
+
+const CHUNK: usize = DEFAULT_CHUNK_SIZE as usize;
+/// Sealed chunk: plaintext plus the AEAD tag.
+const SEALED: usize = CHUNK + 16;
+/// Version 1 header: magic, version, AEAD, chunk size, ciphertext length and
+/// the KEM ciphertext.
+const HEADER: usize = 12 + mce::Ciphertext::LEN;
+
+fn payload(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn single_recipient_round_trips() {
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = mce::generate(&mut rng).unwrap();
+    for aead in Aead::ALL {
+        // Empty, exactly one chunk, and several chunks with a partial tail.
+        for (len, sealed_len) in [
+            (0, HEADER + 16),
+            (CHUNK, HEADER + SEALED),
+            (2 * CHUNK + 5, HEADER + 2 * SEALED + 5 + 16),
+        ] {
+            let plaintext = payload(len);
+            let mut encrypted = Vec::new();
+            let written =
+                file::encrypt(&public_key, aead, &plaintext[..], &mut encrypted, &mut rng).unwrap();
+            assert_eq!(written, len as u64);
+            assert_eq!(encrypted.len(), sealed_len, "{aead}, {len} bytes");
+
+            let mut decrypted = Vec::new();
+            file::decrypt(&secret_key, &encrypted[..], &mut decrypted).unwrap();
+            assert_eq!(decrypted, plaintext, "{aead}, {len} bytes");
+        }
+    }
+}
+
+#[test]
+fn single_recipient_rejects_truncation_and_tampering() {
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = mce::generate(&mut rng).unwrap();
+    let mut encrypted = Vec::new();
+    let plaintext = payload(2 * CHUNK + 5);
+    file::encrypt(
+        &public_key,
+        Aead::default(),
+        &plaintext[..],
+        &mut encrypted,
+        &mut rng,
+    )
+    .unwrap();
+
+    // Dropping the final chunk leaves a file that ends on a chunk boundary.
+    for end in [HEADER + SEALED, HEADER + 2 * SEALED] {
+        assert!(matches!(
+            file::decrypt(&secret_key, &encrypted[..end], &mut Vec::new()),
+            Err(Error::Decryption)
+        ));
+    }
+
+    // AEAD id, chunk size, and the KEM ciphertext.
+    for at in [5, 8, HEADER - 1] {
+        let mut tampered = encrypted.clone();
+        tampered[at] ^= 0x01;
+        assert!(
+            file::decrypt(&secret_key, &tampered[..], &mut Vec::new()).is_err(),
+            "byte {at}"
+        );
+    }
+}
+
+#[test]
+fn every_recipient_decrypts_a_multi_recipient_file() {
+    let mut rng = rand::thread_rng();
+    let keys: Vec<_> = (0..3).map(|_| mce::generate(&mut rng).unwrap()).collect();
+    let (_, outsider) = mce::generate(&mut rng).unwrap();
+    let public_keys: Vec<_> = keys.iter().map(|(pk, _)| pk.clone()).collect();
+    let plaintext: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+
+    let mut encrypted = Vec::new();
+    file::encrypt_to_many(
+        &public_keys,
+        Aead::ChaCha20Poly1305,
+        &plaintext[..],
+        &mut encrypted,
+        &mut rng,
+    )
+    .unwrap();
+    assert_eq!(file::recipients(&encrypted[..]).unwrap().len(), 3);
+
+    for (_, secret_key) in &keys {
+        let mut decrypted = Vec::new();
+        file::decrypt(secret_key, &encrypted[..], &mut decrypted).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+    assert!(matches!(
+        file::decrypt(&outsider, &encrypted[..], &mut Vec::new()),
+        Err(Error::Decryption)
+    ));
+
+    // The recipient list is authenticated along with the payload.
+    encrypted[40] ^= 1;
+    assert!(file::decrypt(&keys[0].1, &encrypted[..], &mut Vec::new()).is_err());
+}
@@ -0,0 +1,59 @@
+use std::collections::HashSet;
+
+use mce::fingerprint::{Fingerprint, SAS_WORDS};
+use mce::PublicKey;
+
+fn counting(start: u8) -> Fingerprint {
+    Fingerprint::from_bytes(std::array::from_fn(|i| start.wrapping_add(i as u8)))
+}
+
+#[test]
+fn renderings() {
+    let fingerprint = counting(0);
+    assert_eq!(
+        fingerprint.to_hex(),
+        "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+    );
+    assert_eq!(
+        fingerprint.to_base32(),
+        "AAAQEAYEAUDAOCAJBIFQYDIOB4IBCEQTCQKRMFYYDENBWHA5DYPQ"
+    );
+    assert_eq!(fingerprint.to_words().split(' ').count(), 32);
+    assert!(fingerprint
+        .to_words()
+        .starts_with(&fingerprint.short_authentication_string()));
+    assert_eq!(
+        fingerprint.short_authentication_string().split(' ').count(),
+        SAS_WORDS
+    );
+    assert_eq!(
+        fingerprint
+            .to_hex()
+            .to_uppercase()
+            .parse::<Fingerprint>()
+            .unwrap(),
+        fingerprint
+    );
+    assert!("0001".parse::<Fingerprint>().is_err());
+
+    let words: HashSet<String> = (0..8)
+        .flat_map(|i| {
+            counting(i * 32)
+                .to_words()
+                .split(' ')
+                .map(String::from)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    assert_eq!(words.len(), 256, "every byte maps to a distinct word");
+}
+
+#[test]
+fn fingerprint_identifies_the_key() {
+    let mut rng = rand::thread_rng();
+    let (first, _) = mce::generate(&mut rng).unwrap();
+    let (second, _) = mce::generate(&mut rng).unwrap();
+    let decoded = PublicKey::from_encoded(&first.to_encoded()).unwrap();
+    assert_eq!(decoded.fingerprint(), first.fingerprint());
+    assert_ne!(first.fingerprint(), second.fingerprint());
+}
@@ -0,0 +1,81 @@
+use mce::hpke::{self, Mode};
+use mce::{Aead, Error};
+
+#[test]
+fn contexts_seal_open_and_export() {
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = mce::generate(&mut rng).unwrap();
+    let psk = [7u8; 32];
+    let modes = [
+        Mode::Base,
+        Mode::Psk {
+            psk: &psk,
+            psk_id: b"key 1",
+        },
+    ];
+    for aead in Aead::ALL {
+        for mode in modes {
+            let (enc, mut sender) =
+                hpke::setup_sender(aead, mode, &public_key, b"info", &mut rng).unwrap();
+            let mut receiver =
+                hpke::setup_receiver(aead, mode, &enc, &secret_key, b"info").unwrap();
+            for i in 0..3u8 {
+                let ct = sender.seal(&[i], &[i; 20]).unwrap();
+                assert_eq!(ct.len(), 20 + 16);
+                assert_eq!(receiver.open(&[i], &ct).unwrap(), [i; 20]);
+            }
+            assert_eq!(
+                *sender.export(b"ctx", 42).unwrap(),
+                *receiver.export(b"ctx", 42).unwrap()
+            );
+        }
+    }
+}
+
+#[test]
+fn open_rejects_mismatched_inputs() {
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = mce::generate(&mut rng).unwrap();
+    let psk = [7u8; 32];
+    let mode = Mode::Psk {
+        psk: &psk,
+        psk_id: b"key 1",
+    };
+    let (enc, ct) = hpke::seal(
+        Aead::Aes128Gcm,
+        mode,
+        &public_key,
+        b"info",
+        b"aad",
+        b"message",
+        &mut rng,
+    )
+    .unwrap();
+    let open = |aead, mode, info: &[u8], aad: &[u8]| {
+        hpke::open(aead, mode, &enc, &secret_key, info, aad, &ct)
+    };
+    assert_eq!(
+        open(Aead::Aes128Gcm, mode, b"info", b"aad").unwrap(),
+        b"message"
+    );
+    assert!(matches!(
+        open(Aead::Aes128Gcm, Mode::Base, b"info", b"aad"),
+        Err(Error::Decryption)
+    ));
+    assert!(matches!(
+        open(Aead::Aes128Gcm, mode, b"other", b"aad"),
+        Err(Error::Decryption)
+    ));
+    assert!(matches!(
+        open(Aead::Aes256Gcm, mode, b"info", b"aad"),
+        Err(Error::Decryption)
+    ));
+    let short = Mode::Psk {
+        psk: &psk[..16],
+        psk_id: b"key 1",
+    };
+    assert!(matches!(
+        open(Aead::Aes128Gcm, short, b"info", b"aad"),
+        Err(Error::Protocol(_))
+    ));
+}
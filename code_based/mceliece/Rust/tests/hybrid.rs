@@ -0,0 +1,60 @@
+use mce::hybrid::{self, Ciphertext, PublicKey, SecretKey};
+use mce::Error;
+
+#[test]
+fn round_trip() {
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = hybrid::generate(&mut rng).unwrap();
+
+    let public_key = PublicKey::from_bytes(&public_key.to_bytes()).unwrap();
+    let secret_key = SecretKey::from_bytes(&secret_key.to_bytes()).unwrap();
+    assert_eq!(
+        secret_key.x25519_public().as_bytes(),
+        &public_key.to_bytes()[PublicKey::LEN - 32..]
+    );
+
+    let (ciphertext, sent) = hybrid::encapsulate(&public_key, &mut rng).unwrap();
+    let ciphertext = Ciphertext::from_bytes(&ciphertext.to_bytes()).unwrap();
+    let received = hybrid::decapsulate(&ciphertext, &secret_key).unwrap();
+    assert_eq!(sent.as_bytes(), received.as_bytes());
+}
+
+#[test]
+fn either_component_changes_the_secret() {
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = hybrid::generate(&mut rng).unwrap();
+    let (ciphertext, sent) = hybrid::encapsulate(&public_key, &mut rng).unwrap();
+    let bytes = ciphertext.to_bytes();
+
+    // First byte of the McEliece ciphertext, then of the X25519 ephemeral key.
+    for at in [0, mce::Ciphertext::LEN] {
+        let mut tampered = bytes.clone();
+        tampered[at] ^= 0x01;
+        let tampered = Ciphertext::from_bytes(&tampered).unwrap();
+        let received = hybrid::decapsulate(&tampered, &secret_key).unwrap();
+        assert_ne!(sent.as_bytes(), received.as_bytes(), "byte {at}");
+    }
+}
+
+#[test]
+fn rejects_low_order_points() {
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = hybrid::generate(&mut rng).unwrap();
+
+    let mut bytes = public_key.to_bytes();
+    bytes[PublicKey::LEN - 32..].fill(0);
+    let weak_key = PublicKey::from_bytes(&bytes).unwrap();
+    assert!(matches!(
+        hybrid::encapsulate(&weak_key, &mut rng),
+        Err(Error::LowOrderPoint)
+    ));
+
+    let (ciphertext, _) = hybrid::encapsulate(&public_key, &mut rng).unwrap();
+    let mut bytes = ciphertext.to_bytes();
+    bytes[Ciphertext::LEN - 32..].fill(0);
+    let weak_ciphertext = Ciphertext::from_bytes(&bytes).unwrap();
+    assert!(matches!(
+        hybrid::decapsulate(&weak_ciphertext, &secret_key),
+        Err(Error::LowOrderPoint)
+    ));
+}
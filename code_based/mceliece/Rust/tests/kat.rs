@@ -0,0 +1,187 @@
+//! Known-answer tests against the NIST PQC reference vectors.
+//!
+//! `PQCgenKAT_kem` seeds the NIST AES-256-CTR DRBG with each vector's `seed`
+//! and then runs key generation, encapsulation and decapsulation off that one
+//! stream. Replaying the same sequence here must reproduce `pk`, `sk`, `ct`
+//! and `ss` byte for byte.
+//!
+//! The full response files are large (tens of megabytes per set), so they are
+//! not checked in. Point `MCE_KAT_DIR` at a directory holding
+//! `<parameter set>.rsp`, e.g. `mceliece348864.rsp`, and run the ignored
+//! tests to check every vector for the compiled set (`make kat`). The first
+//! vector of the default set is embedded below as digests and checked in
+//! every build of that set.
+
+use std::{env, fs, path::PathBuf};
+
+use mce::{NistDrbg, ParameterSet};
+use rand::RngCore;
+
+/// One `count = ...` record of a response file.
+struct Vector {
+    count: usize,
+    seed: [u8; 48],
+    pk: Vec<u8>,
+    sk: Vec<u8>,
+    ct: Vec<u8>,
+    ss: Vec<u8>,
+}
+
+/// Parses the records of a `.rsp` file, ignoring comments and unknown fields.
+fn parse_rsp(text: &str) -> Vec<Vector> {
+    let mut vectors = Vec::new();
+    let mut fields: Vec<(&str, &str)> = Vec::new();
+    let mut flush = |fields: &mut Vec<(&str, &str)>| {
+        if fields.is_empty() {
+            return;
+        }
+        let get = |name: &str| {
+            let value = fields
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+                .unwrap_or_else(|| panic!("record without `{name}`"));
+            hex::decode(value).unwrap_or_else(|err| panic!("bad hex in `{name}`: {err}"))
+        };
+        let count = fields
+            .iter()
+            .find(|(key, _)| *key == "count")
+            .and_then(|(_, value)| value.parse().ok())
+            .expect("record without a valid `count`");
+        vectors.push(Vector {
+            count,
+            seed: get("seed").try_into().expect("48-byte seed"),
+            pk: get("pk"),
+            sk: get("sk"),
+            ct: get("ct"),
+            ss: get("ss"),
+        });
+        fields.clear();
+    };
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        let (key, value) = line.split_once('=').expect("`key = value` line");
+        let key = key.trim();
+        if key == "count" {
+            flush(&mut fields);
+        }
+        fields.push((key, value.trim()));
+    }
+    flush(&mut fields);
+    vectors
+}
+
+type Outputs = (
+    mce::PublicKey,
+    mce::SecretKey,
+    mce::Ciphertext,
+    mce::SharedSecret,
+);
+
+/// Replays `PQCgenKAT_kem` for one seed.
+fn replay(seed: &[u8; 48]) -> Outputs {
+    let mut rng = NistDrbg::new(seed);
+    let (pk, sk) = mce::generate(&mut rng).unwrap();
+    let (ct, ss) = mce::encapsulate(&pk, &mut rng).unwrap();
+    let recovered = mce::decapsulate(&ct, &sk).unwrap();
+    assert_eq!(ss.as_bytes(), recovered.as_bytes());
+    (pk, sk, ct, ss)
+}
+
+#[test]
+fn drbg_matches_reference_output() {
+    // First 64 bytes drawn after `randombytes_init` with entropy 0, 1, ..., 47.
+    let mut rng = NistDrbg::new(&std::array::from_fn(|i| i as u8));
+    let mut out = [0u8; 64];
+    rng.fill_bytes(&mut out);
+    assert_eq!(
+        hex::encode_upper(out),
+        "061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7\
+         056A8C266F9EF97ED08541DBD2E1FFA19810F5392D076276EF41277C3AB6E94A"
+    );
+}
+
+#[test]
+#[ignore = "needs the response files in MCE_KAT_DIR; run with `make kat`"]
+fn response_file_matches() {
+    let dir =
+        env::var_os("MCE_KAT_DIR").expect("MCE_KAT_DIR must name the response file directory");
+    let params = ParameterSet::compiled();
+    let path = PathBuf::from(dir).join(format!("{params}.rsp"));
+    let text = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("cannot read {}: {err}", path.display()));
+    let vectors = parse_rsp(&text);
+    assert!(!vectors.is_empty(), "no vectors in {}", path.display());
+
+    for vector in &vectors {
+        let (pk, sk, ct, ss) = replay(&vector.seed);
+        // Compare without `assert_eq!` so a mismatch does not dump the keys.
+        let count = vector.count;
+        assert!(pk.as_bytes()[..] == vector.pk[..], "pk of vector {count}");
+        assert!(sk.as_bytes()[..] == vector.sk[..], "sk of vector {count}");
+        assert!(ct.as_bytes()[..] == vector.ct[..], "ct of vector {count}");
+        assert!(ss.as_bytes()[..] == vector.ss[..], "ss of vector {count}");
+    }
+}
+
+/// The first vector of mceliece348864, which the backend builds when no other
+/// set is selected.
+#[cfg(not(any(
+    feature = "mceliece348864f",
+    feature = "mceliece460896",
+    feature = "mceliece460896f",
+    feature = "mceliece6688128",
+    feature = "mceliece6688128f",
+    feature = "mceliece6960119",
+    feature = "mceliece6960119f",
+    feature = "mceliece8192128",
+    feature = "mceliece8192128f",
+)))]
+mod mceliece348864 {
+    use sha3::{Digest, Sha3_256};
+
+    use super::*;
+
+    // count = 0. The keys are given as SHA3-256 digests.
+    const PK_SHA3: &str = "2404ae3dca6800fcdff9b46ae7cb3f7a89915bb83cb880129d57a570f4a3e9ff";
+    const SK_SHA3: &str = "8dc09d86e9020296b048571d462e8177d0a278a7fde3a8028e22da208baed3d4";
+    const CT: &str = "DEF61908A70A3099E45B4D5D91957ADE70F571D210D525D655DB7294515F91D9\
+                      7795F2353615BC7CDF13502181E5BCC8C9ABFEF31819D66DD2760363694F7896\
+                      02264A3E24445681A0183CE343A2264FDFF96C82AB318AE888D105D52D59BC1B";
+    const SS: &str = "B4F9FF1E4390E3BE0BBCEBFF9A525AE83B191211896AA8786CE8BC511C9F78C3";
+
+    /// The seed of the `count`-th vector: `PQCgenKAT_kem` draws 48 bytes per
+    /// vector from a DRBG seeded with the bytes 0, 1, ..., 47.
+    fn reference_seed(count: usize) -> [u8; 48] {
+        let mut master = NistDrbg::new(&std::array::from_fn(|i| i as u8));
+        let mut seed = [0u8; 48];
+        for _ in 0..=count {
+            master.fill_bytes(&mut seed);
+        }
+        seed
+    }
+
+    fn sha3(bytes: &[u8]) -> String {
+        hex::encode(Sha3_256::digest(bytes))
+    }
+
+    #[test]
+    fn first_vector_matches_reference() {
+        assert_eq!(ParameterSet::compiled(), ParameterSet::McEliece348864);
+        let seed = reference_seed(0);
+        assert_eq!(
+            hex::encode_upper(seed),
+            "061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7\
+             056A8C266F9EF97ED08541DBD2E1FFA1"
+        );
+
+        let (pk, sk, ct, ss) = replay(&seed);
+        assert_eq!(sha3(pk.as_bytes()), PK_SHA3);
+        assert_eq!(sha3(sk.as_bytes()), SK_SHA3);
+        assert_eq!(hex::encode_upper(ct.as_bytes()), CT);
+        assert_eq!(hex::encode_upper(ss.as_bytes()), SS);
+    }
+}
@@ -0,0 +1,100 @@
+use mce::kdf::{derive_keys, Kdf, MAX_KEY_LEN};
+use mce::Error;
+
+const LENGTHS: [usize; 3] = [32, 12, 64];
+
+#[test]
+fn sender_and_receiver_agree_and_inputs_are_bound() {
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = mce::generate(&mut rng).unwrap();
+    let (other_key, _) = mce::generate(&mut rng).unwrap();
+    let (ciphertext, sent) = mce::encapsulate(&public_key, &mut rng).unwrap();
+    let (other_ciphertext, _) = mce::encapsulate(&public_key, &mut rng).unwrap();
+    let received = mce::decapsulate(&ciphertext, &secret_key).unwrap();
+
+    for kdf in [Kdf::HkdfSha256, Kdf::HkdfSha3_256] {
+        let derive = |public_key, ciphertext, info: &[u8], context: &[u8]| {
+            derive_keys(kdf, &sent, public_key, ciphertext, info, context, &LENGTHS).unwrap()
+        };
+        let keys = derive(&public_key, &ciphertext, b"app", b"alice bob");
+        let lengths: Vec<usize> = keys.iter().map(|key| key.len()).collect();
+        assert_eq!(lengths, LENGTHS);
+        assert_ne!(keys[0][..12], keys[1][..]);
+
+        let receiver = derive_keys(
+            kdf,
+            &received,
+            &public_key,
+            &ciphertext,
+            b"app",
+            b"alice bob",
+            &LENGTHS,
+        )
+        .unwrap();
+        assert_eq!(keys, receiver);
+
+        for changed in [
+            derive(&other_key, &ciphertext, b"app", b"alice bob"),
+            derive(&public_key, &other_ciphertext, b"app", b"alice bob"),
+            derive(&public_key, &ciphertext, b"app2", b"alice bob"),
+            derive(&public_key, &ciphertext, b"app", b"alice carol"),
+        ] {
+            for (key, changed) in keys.iter().zip(&changed) {
+                assert_ne!(key, changed);
+            }
+        }
+    }
+    let sha2 = derive_keys(
+        Kdf::HkdfSha256,
+        &sent,
+        &public_key,
+        &ciphertext,
+        b"",
+        b"",
+        &[32],
+    )
+    .unwrap();
+    let sha3 = derive_keys(
+        Kdf::HkdfSha3_256,
+        &sent,
+        &public_key,
+        &ciphertext,
+        b"",
+        b"",
+        &[32],
+    )
+    .unwrap();
+    assert_ne!(sha2, sha3);
+}
+
+#[test]
+fn lengths_are_independent_and_bounded() {
+    let mut rng = rand::thread_rng();
+    let (public_key, _) = mce::generate(&mut rng).unwrap();
+    let (ciphertext, shared_secret) = mce::encapsulate(&public_key, &mut rng).unwrap();
+    let derive = |lengths: &[usize]| {
+        derive_keys(
+            Kdf::default(),
+            &shared_secret,
+            &public_key,
+            &ciphertext,
+            b"app",
+            b"",
+            lengths,
+        )
+    };
+
+    let keys = derive(&LENGTHS).unwrap();
+    let resized = derive(&[32, 16, 64]).unwrap();
+    assert_eq!(keys[0], resized[0]);
+    assert_ne!(keys[1][..], resized[1][..12]);
+    assert_eq!(keys[2], resized[2]);
+
+    assert_eq!(derive(&[MAX_KEY_LEN]).unwrap()[0].len(), MAX_KEY_LEN);
+    for bad in [0, MAX_KEY_LEN + 1] {
+        assert!(matches!(
+            derive(&[32, bad]),
+            Err(Error::InvalidKeyLength(len)) if len == bad
+        ));
+    }
+}
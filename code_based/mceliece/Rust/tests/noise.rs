@@ -0,0 +1,97 @@
+use mce::noise::{HandshakeState, Pattern, Role};
+use mce::Error;
+
+fn handshake(
+    pattern: Pattern,
+    mut initiator: HandshakeState,
+    mut responder: HandshakeState,
+) -> (HandshakeState, HandshakeState) {
+    let mut rng = rand::thread_rng();
+    let (mut writer, mut reader) = (&mut initiator, &mut responder);
+    let mut turn = 0;
+    while !writer.is_finished() {
+        let payload = format!("{} message {turn}", pattern.name());
+        let message = writer.write_message(payload.as_bytes(), &mut rng).unwrap();
+        assert_eq!(reader.read_message(&message).unwrap(), payload.as_bytes());
+        std::mem::swap(&mut writer, &mut reader);
+        turn += 1;
+    }
+    assert!(reader.is_finished());
+    assert_eq!(initiator.handshake_hash(), responder.handshake_hash());
+    (initiator, responder)
+}
+
+fn check_transport(initiator: HandshakeState, responder: HandshakeState) {
+    let (mut i_send, mut i_recv) = initiator.split().unwrap();
+    let (mut r_send, mut r_recv) = responder.split().unwrap();
+    for i in 0..3u8 {
+        let ct = i_send.encrypt_with_ad(b"ad", &[i; 10]).unwrap();
+        assert_eq!(r_recv.decrypt_with_ad(b"ad", &ct).unwrap(), [i; 10]);
+        let ct = r_send.encrypt_with_ad(b"", &[i; 3]).unwrap();
+        assert_eq!(i_recv.decrypt_with_ad(b"", &ct).unwrap(), [i; 3]);
+    }
+    let mut ct = i_send.encrypt_with_ad(b"", b"tampered").unwrap();
+    ct[0] ^= 1;
+    assert!(matches!(
+        r_recv.decrypt_with_ad(b"", &ct),
+        Err(Error::Decryption)
+    ));
+}
+
+#[test]
+fn patterns_complete_and_split() {
+    let mut rng = rand::thread_rng();
+
+    let initiator = HandshakeState::new(Pattern::NN, Role::Initiator, b"p", None, None).unwrap();
+    let responder = HandshakeState::new(Pattern::NN, Role::Responder, b"p", None, None).unwrap();
+    let (initiator, responder) = handshake(Pattern::NN, initiator, responder);
+    check_transport(initiator, responder);
+
+    let alice = mce::generate(&mut rng).unwrap();
+    let bob = mce::generate(&mut rng).unwrap();
+    let (alice_pk, bob_pk) = (alice.0.clone(), bob.0.clone());
+    let initiator =
+        HandshakeState::new(Pattern::XX, Role::Initiator, b"", Some(alice), None).unwrap();
+    let responder =
+        HandshakeState::new(Pattern::XX, Role::Responder, b"", Some(bob), None).unwrap();
+    let (initiator, responder) = handshake(Pattern::XX, initiator, responder);
+    assert_eq!(initiator.remote_static(), Some(&bob_pk));
+    assert_eq!(responder.remote_static(), Some(&alice_pk));
+    check_transport(initiator, responder);
+
+    let alice = mce::generate(&mut rng).unwrap();
+    let bob = mce::generate(&mut rng).unwrap();
+    let bob_pk = bob.0.clone();
+    let initiator =
+        HandshakeState::new(Pattern::IK, Role::Initiator, b"", Some(alice), Some(bob_pk)).unwrap();
+    let responder =
+        HandshakeState::new(Pattern::IK, Role::Responder, b"", Some(bob), None).unwrap();
+    let (initiator, responder) = handshake(Pattern::IK, initiator, responder);
+    check_transport(initiator, responder);
+}
+
+#[test]
+fn handshake_rejects_misuse_and_mismatches() {
+    let mut rng = rand::thread_rng();
+    assert!(matches!(
+        HandshakeState::new(Pattern::XX, Role::Initiator, b"", None, None),
+        Err(Error::Protocol(_))
+    ));
+
+    let mut initiator =
+        HandshakeState::new(Pattern::NN, Role::Initiator, b"one", None, None).unwrap();
+    let mut responder =
+        HandshakeState::new(Pattern::NN, Role::Responder, b"two", None, None).unwrap();
+    assert!(matches!(
+        responder.write_message(b"", &mut rng),
+        Err(Error::Protocol(_))
+    ));
+    let message = initiator.write_message(b"", &mut rng).unwrap();
+    responder.read_message(&message).unwrap();
+    let message = responder.write_message(b"", &mut rng).unwrap();
+    // Different prologues give different keys, so the payload fails to open.
+    assert!(matches!(
+        initiator.read_message(&message),
+        Err(Error::Decryption)
+    ));
+}
@@ -0,0 +1,57 @@
+use mce::passphrase::{self, KdfParams};
+use mce::{Error, SecretKey};
+
+/// Cheap enough for tests; real keys use the default.
+const FAST: KdfParams = KdfParams {
+    memory_kib: 64,
+    iterations: 1,
+    parallelism: 1,
+};
+
+#[test]
+fn round_trip_and_wrong_passphrase() {
+    let mut rng = rand::thread_rng();
+    let (_, secret_key) = mce::generate(&mut rng).unwrap();
+    let container = secret_key
+        .to_protected(b"correct horse", FAST, &mut rng)
+        .unwrap();
+    assert!(passphrase::is_protected(&container));
+
+    let opened = SecretKey::from_protected(&container, b"correct horse").unwrap();
+    assert_eq!(*opened.to_encoded(), *secret_key.to_encoded());
+    assert!(matches!(
+        SecretKey::from_protected(&container, b"battery staple"),
+        Err(Error::Decryption)
+    ));
+}
+
+#[test]
+fn header_is_authenticated() {
+    let mut rng = rand::thread_rng();
+    let container = passphrase::protect(b"not really a key", b"pw", FAST, &mut rng).unwrap();
+    assert_eq!(
+        &passphrase::unprotect(&container, b"pw").unwrap()[..],
+        b"not really a key"
+    );
+
+    // Memory cost, iteration count, and the first and last salt bytes.
+    for at in [11, 15, 20, 35] {
+        let mut tampered = container.clone();
+        tampered[at] ^= 0x02;
+        assert!(
+            matches!(
+                passphrase::unprotect(&tampered, b"pw"),
+                Err(Error::Decryption)
+            ),
+            "byte {at}"
+        );
+    }
+
+    // Costs beyond the caps are refused before any work is done.
+    let mut expensive = container.clone();
+    expensive[12..16].copy_from_slice(&(passphrase::MAX_ITERATIONS + 1).to_be_bytes());
+    assert!(matches!(
+        passphrase::unprotect(&expensive, b"pw"),
+        Err(Error::Format(_))
+    ));
+}
@@ -0,0 +1,69 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use mce::KeyPool;
+
+/// Pools in concurrently running tests would see each other's workers.
+static SERIAL: Mutex<()> = Mutex::new(());
+
+fn serial() -> std::sync::MutexGuard<'static, ()> {
+    SERIAL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[test]
+fn take_waits_for_a_usable_keypair() {
+    let _serial = serial();
+    let pool = KeyPool::new(1, 1);
+    // Generating a key takes far longer than getting here.
+    assert!(pool.try_take().is_none());
+
+    let (public_key, secret_key) = pool.take();
+    let mut rng = rand::thread_rng();
+    let (ciphertext, sent) = mce::encapsulate(&public_key, &mut rng).unwrap();
+    let received = mce::decapsulate(&ciphertext, &secret_key).unwrap();
+    assert_eq!(sent.as_bytes(), received.as_bytes());
+}
+
+#[test]
+fn refills_to_target() {
+    let _serial = serial();
+    let pool = KeyPool::new(2, 2);
+    let (first, _) = pool.take();
+    let deadline = Instant::now() + Duration::from_secs(120);
+    while pool.len() < pool.target() {
+        assert!(Instant::now() < deadline, "pool did not refill");
+        thread::sleep(Duration::from_millis(20));
+    }
+    // Never overshoots, and hands out distinct keys.
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(pool.len(), 2);
+    let (second, _) = pool.try_take().unwrap();
+    assert_ne!(first.as_bytes(), second.as_bytes());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn drop_joins_workers() {
+    fn workers() -> usize {
+        std::fs::read_dir("/proc/self/task")
+            .unwrap()
+            .filter_map(|task| std::fs::read_to_string(task.ok()?.path().join("comm")).ok())
+            .filter(|name| name.starts_with("mce-keypool-"))
+            .count()
+    }
+
+    let _serial = serial();
+    let pool = KeyPool::new(1, 3);
+    // Threads name themselves once they start running.
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while workers() < 3 {
+        assert!(Instant::now() < deadline, "workers did not start");
+        thread::sleep(Duration::from_millis(10));
+    }
+    // Some workers are idle and one is generating a key.
+    drop(pool);
+    assert_eq!(workers(), 0);
+}
@@ -0,0 +1,95 @@
+use std::io::Read;
+
+use mce::{Error, PublicKey, PublicKeyReader};
+
+/// Yields its input a few bytes at a time, like a slow socket.
+struct Trickle<'a>(&'a [u8], usize);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(self.1).min(self.0.len());
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn reader_accepts_arbitrary_chunks() {
+    let (public_key, _) = mce::generate(&mut rand::thread_rng()).unwrap();
+    let mut stream = public_key.to_encoded();
+    stream.extend_from_slice(b"next message");
+
+    for chunk_len in [1, 7, 4096, stream.len()] {
+        let mut reader = PublicKeyReader::new();
+        let mut used = 0;
+        for chunk in stream.chunks(chunk_len) {
+            used += reader.update(chunk).unwrap();
+            if reader.is_complete() {
+                break;
+            }
+        }
+        assert_eq!(&stream[used..], b"next message");
+        assert_eq!(reader.finish().unwrap(), public_key);
+    }
+
+    let from_read = PublicKeyReader::raw()
+        .read_from(Trickle(public_key.as_bytes(), 1000))
+        .unwrap();
+    assert_eq!(from_read, public_key);
+}
+
+#[test]
+fn reader_rejects_bad_input_early() {
+    let (public_key, secret_key) = mce::generate(&mut rand::thread_rng()).unwrap();
+
+    // The header alone is enough to reject a secret key.
+    let encoded = secret_key.to_encoded();
+    assert!(matches!(
+        PublicKeyReader::new().update(&encoded[..12]),
+        Err(Error::WrongKind { .. })
+    ));
+
+    let mut encoded = public_key.to_encoded();
+    let mut reader = PublicKeyReader::new();
+    reader.update(&encoded[..1000]).unwrap();
+    assert_eq!(reader.remaining(), encoded.len() - 1000);
+    assert!(matches!(reader.finish(), Err(Error::InvalidLength { .. })));
+
+    assert!(matches!(
+        PublicKeyReader::new().read_from(&encoded[..5000]),
+        Err(Error::Format(_))
+    ));
+
+    encoded[500] ^= 1;
+    assert!(matches!(
+        PublicKeyReader::new().read_from(&encoded[..]),
+        Err(Error::Checksum)
+    ));
+    assert_eq!(
+        PublicKey::LEN,
+        PublicKeyReader::raw().total_len(),
+        "the raw form has no framing"
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn reader_reads_async() {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    let (public_key, _) = mce::generate(&mut rand::thread_rng()).unwrap();
+    let encoded = public_key.to_encoded();
+    // Reading from a slice never blocks, so polling to completion needs no
+    // runtime.
+    let mut future = pin!(PublicKeyReader::new().read_from_async(&encoded[..]));
+    let mut cx = Context::from_waker(Waker::noop());
+    let result = loop {
+        if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+            break result;
+        }
+    };
+    assert_eq!(result.unwrap(), public_key);
+}
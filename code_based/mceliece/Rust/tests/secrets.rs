@@ -0,0 +1,44 @@
+use mce::{hybrid, SecretKey};
+
+#[test]
+fn debug_is_redacted() {
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = mce::generate(&mut rng).unwrap();
+    let (_, shared_secret) = mce::encapsulate(&public_key, &mut rng).unwrap();
+    let (_, hybrid_key) = hybrid::generate(&mut rng).unwrap();
+
+    assert_eq!(format!("{secret_key:?}"), r#"SecretKey("-- redacted --")"#);
+    assert_eq!(
+        format!("{shared_secret:?}"),
+        r#"SharedSecret("-- redacted --")"#
+    );
+    assert_eq!(format!("{hybrid_key:?}"), r#"SecretKey("-- redacted --")"#);
+}
+
+#[test]
+fn equality_compares_every_byte() {
+    let mut rng = rand::thread_rng();
+    let (public_key, secret_key) = mce::generate(&mut rng).unwrap();
+    let copy = SecretKey::from_bytes(secret_key.as_bytes()).unwrap();
+    assert_eq!(secret_key, copy);
+    let mut bytes = secret_key.as_bytes().to_vec();
+    *bytes.last_mut().unwrap() ^= 0x01;
+    assert_ne!(secret_key, SecretKey::from_bytes(&bytes).unwrap());
+
+    let (ciphertext, sent) = mce::encapsulate(&public_key, &mut rng).unwrap();
+    let received = mce::decapsulate(&ciphertext, &secret_key).unwrap();
+    assert_eq!(sent, received);
+    let (_, other) = mce::encapsulate(&public_key, &mut rng).unwrap();
+    assert_ne!(sent, other);
+
+    let (_, hybrid_key) = hybrid::generate(&mut rng).unwrap();
+    let bytes = hybrid_key.to_bytes();
+    assert_eq!(hybrid_key, hybrid::SecretKey::from_bytes(&bytes).unwrap());
+    // The first byte of the McEliece component, then the last of the X25519 one.
+    for at in [0, bytes.len() - 1] {
+        let mut changed = bytes.to_vec();
+        changed[at] ^= 0x01;
+        let changed = hybrid::SecretKey::from_bytes(&changed).unwrap();
+        assert_ne!(hybrid_key, changed, "byte {at}");
+    }
+}
@@ -0,0 +1,16 @@
+#[test]
+fn seed_determines_the_keypair() {
+    let (pk_a, sk_a) = mce::generate_from_seed(&[1; 32]).unwrap();
+    let (pk_b, sk_b) = mce::generate_from_seed(&[1; 32]).unwrap();
+    assert_eq!(pk_a.as_bytes(), pk_b.as_bytes());
+    assert_eq!(sk_a.as_bytes(), sk_b.as_bytes());
+
+    let (pk_c, sk_c) = mce::generate_from_seed(&[2; 32]).unwrap();
+    assert_ne!(pk_a.as_bytes(), pk_c.as_bytes());
+    assert_ne!(sk_a.as_bytes(), sk_c.as_bytes());
+
+    let mut rng = rand::thread_rng();
+    let (ciphertext, sent) = mce::encapsulate(&pk_b, &mut rng).unwrap();
+    let received = mce::decapsulate(&ciphertext, &sk_a).unwrap();
+    assert_eq!(sent.as_bytes(), received.as_bytes());
+}